@@ -0,0 +1,181 @@
+//! Helpers for programs communicating with ASCII codes (such as the ones in days 17 and 25)
+
+use alloc::{string::String, vec::Vec};
+
+use num::{Integer, ToPrimitive};
+
+use crate::{
+    error::{self, VMError},
+    IntcodeVM, VMResult,
+};
+
+impl<T> IntcodeVM<T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    /// Runs an ASCII program drawing a scaffold view (as in day 17) to halt,
+    /// then returns the `(x, y)` coordinates of the scaffold intersections
+    ///
+    /// An intersection is a scaffold (`#`, or the robot `^`, `v`, `<`, `>`) with scaffolds
+    /// above, below, left and right of it.
+    ///
+    /// # Errors
+    ///
+    /// - [`VMError::InvalidAsciiOutput`] if the program outputs something else than an ASCII code
+    /// - [`VMError::UnexpectedInputRequest`] if the program asks for an input
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::IntcodeVM;
+    /// let view = ".#.\n###\n.#.\n";
+    /// let mut program: Vec<i64> = view.bytes().flat_map(|b| [104, b.into()]).collect();
+    /// program.push(99);
+    ///
+    /// let mut vm = IntcodeVM::new(program);
+    /// assert_eq!(vm.scaffold_intersections().unwrap(), vec![(1, 1)]);
+    /// ```
+    pub fn scaffold_intersections(&mut self) -> error::Result<Vec<(usize, usize)>, T> {
+        let mut view = String::new();
+        loop {
+            match self.run()? {
+                VMResult::OutOfFuel => return Err(VMError::OutOfFuel),
+                VMResult::Output(out) => view.push(ascii_char(out)?),
+                VMResult::WaitingForInput => return Err(VMError::UnexpectedInputRequest),
+                VMResult::BreakpointHit(_) | VMResult::WatchpointHit { .. } => (),
+                _ => break,
+            }
+        }
+
+        Ok(scaffold_intersections(&view))
+    }
+}
+
+impl<T> IntcodeVM<T>
+where
+    T: Integer + Clone + ToPrimitive + From<u8>,
+{
+    /// Adds the ASCII code of every character of `line` to the input queue,
+    /// followed by a newline if `line` does not already end with one
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VMError::NonAsciiInput`] (without queuing anything) if `line` contains a character that is not ASCII
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::VMError, IntcodeVM};
+    /// let mut vm = IntcodeVM::<i64>::new([99]);
+    /// vm.push_ascii_line("take mug").unwrap();
+    /// assert_eq!(vm.get_next_input(), &Some(116));
+    ///
+    /// assert!(matches!(vm.push_ascii_line("go ↑"), Err(VMError::NonAsciiInput('↑'))));
+    /// ```
+    pub fn push_ascii_line(&mut self, line: &str) -> error::Result<(), T> {
+        if let Some(c) = line.chars().find(|c| !c.is_ascii()) {
+            return Err(VMError::NonAsciiInput(c));
+        }
+
+        self.extend_input(line.bytes().map(T::from));
+        if !line.ends_with('\n') {
+            self.push_input(T::from(b'\n'));
+        }
+
+        Ok(())
+    }
+}
+
+/// Adds every byte of `line`, followed by a newline, to the input queue of `vm`
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::{ascii::feed_line, IntcodeVM};
+/// let mut vm = IntcodeVM::<i64>::new([99]);
+/// feed_line(&mut vm, "NOT A J");
+/// assert_eq!(vm.get_next_input(), &Some(78));
+/// ```
+pub fn feed_line<T>(vm: &mut IntcodeVM<T>, line: &str)
+where
+    T: Integer + Clone + ToPrimitive + From<u8>,
+{
+    vm.extend_input(line.bytes().chain([b'\n']).map(T::from));
+}
+
+/// Runs `vm` until it halts (or waits for an input while its input queue is empty),
+/// returning its ASCII outputs as a [String] along with the other outputs
+/// (such as the answer of the puzzle, usually a big number at the end)
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::{ascii::{collect_ascii, feed_line}, IntcodeVM};
+/// let mut vm = IntcodeVM::<i64>::new([
+///     3, 16, 4, 16, // echoes a character
+///     1001, 17, -1, 17, 1005, 17, 0, // 3 times
+///     104, 1000, 99, // then outputs 1000
+///     0, 0, 0, 3,
+/// ]);
+/// feed_line(&mut vm, "ok");
+///
+/// let (text, others) = collect_ascii(&mut vm).unwrap();
+/// assert_eq!(text, "ok\n");
+/// assert_eq!(others, [1000]);
+/// ```
+pub fn collect_ascii<T>(vm: &mut IntcodeVM<T>) -> error::Result<(String, Vec<T>), T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    let mut text = String::new();
+    let mut others = Vec::new();
+    loop {
+        match vm.run()? {
+            VMResult::OutOfFuel => return Err(VMError::OutOfFuel),
+            VMResult::Output(out) => match ascii_char(out) {
+                Ok(c) => text.push(c),
+                Err(VMError::InvalidAsciiOutput(out)) => others.push(out),
+                Err(err) => return Err(err),
+            },
+            VMResult::BreakpointHit(_) | VMResult::WatchpointHit { .. } => (),
+            _ => return Ok((text, others)),
+        }
+    }
+}
+
+/// Converts an output to the ASCII character it represents
+#[inline]
+fn ascii_char<T>(value: T) -> error::Result<char, T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    match value.to_u8() {
+        Some(code) if code.is_ascii() => Ok(code.into()),
+        _ => Err(VMError::InvalidAsciiOutput(value)),
+    }
+}
+
+fn scaffold_intersections(view: &str) -> Vec<(usize, usize)> {
+    let grid: Vec<&[u8]> = view.lines().map(str::as_bytes).collect();
+    let is_scaffold = |x: usize, y: usize| {
+        grid.get(y)
+            .and_then(|row| row.get(x))
+            .is_some_and(|cell| matches!(cell, b'#' | b'^' | b'v' | b'<' | b'>'))
+    };
+
+    let mut intersections = Vec::new();
+    for (y, row) in grid.iter().enumerate().skip(1) {
+        for x in 1..row.len() {
+            if is_scaffold(x, y)
+                && is_scaffold(x - 1, y)
+                && is_scaffold(x + 1, y)
+                && is_scaffold(x, y - 1)
+                && is_scaffold(x, y + 1)
+            {
+                intersections.push((x, y));
+            }
+        }
+    }
+
+    intersections
+}
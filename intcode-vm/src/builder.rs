@@ -0,0 +1,245 @@
+use alloc::{collections::BTreeSet, vec::Vec};
+
+use num::{Integer, ToPrimitive};
+
+use crate::{
+    memory::Memory,
+    vm::{EmptyInputBehavior, HaltBehavior},
+    IntcodeVM,
+};
+
+/// Builds an [IntcodeVM] with every option set in a single expression
+/// (see [`IntcodeVM::builder()`](IntcodeVM::builder))
+///
+/// Each setter corresponds to a method of the VM, the options that are not set keep their default.
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::{error::VMError, vm::HaltBehavior, IntcodeVM, VMResult};
+/// // outputs its 2 inputs then writes far away
+/// let mut vm = IntcodeVM::builder()
+///     .memory([3, 0, 4, 0, 3, 0, 4, 0, 1101, 1, 1, 5000, 99])
+///     .inputs([7, 8])
+///     .max_memory(1024)
+///     .strict(true)
+///     .code_limit(13)
+///     .instruction_limit(1000)
+///     .halt_behavior(HaltBehavior::Pause)
+///     .breakpoints([4])
+///     .trace()
+///     .build();
+///
+/// assert_eq!(vm.run().unwrap(), VMResult::Output(7));
+/// assert_eq!(vm.run().unwrap(), VMResult::BreakpointHit(4));
+/// assert_eq!(vm.run().unwrap(), VMResult::Output(8));
+/// assert!(matches!(vm.run(), Err(VMError::MemoryLimitExceeded { address: 5000, .. })));
+/// assert_eq!(vm.trace().unwrap().outputs(), &[7, 8]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IntcodeVMBuilder<T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    memory: Memory<T>,
+    inputs: Vec<T>,
+    max_memory: Option<usize>,
+    strict: bool,
+    code_limit: Option<usize>,
+    instruction_limit: Option<u64>,
+    fuel: Option<u64>,
+    halt_behavior: HaltBehavior,
+    input_on_empty: EmptyInputBehavior<T>,
+    breakpoints: BTreeSet<usize>,
+    buffer_output: bool,
+    trace: bool,
+    access_profiling: bool,
+    profiling: bool,
+    input_logging: bool,
+}
+
+impl<T> Default for IntcodeVMBuilder<T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    #[inline]
+    fn default() -> Self {
+        Self {
+            memory: Memory::from_vec(Vec::new()),
+            inputs: Vec::new(),
+            max_memory: None,
+            strict: false,
+            code_limit: None,
+            instruction_limit: None,
+            fuel: None,
+            halt_behavior: HaltBehavior::default(),
+            input_on_empty: EmptyInputBehavior::default(),
+            breakpoints: BTreeSet::new(),
+            buffer_output: false,
+            trace: false,
+            access_profiling: false,
+            profiling: false,
+            input_logging: false,
+        }
+    }
+}
+
+impl<T> IntcodeVMBuilder<T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    /// Program of the VM (empty if it is not set)
+    #[inline]
+    pub fn memory<I: Into<Memory<T>>>(mut self, memory: I) -> Self {
+        self.memory = memory.into();
+        self
+    }
+
+    /// Values queued as inputs of the VM (see [`IntcodeVM::extend_input()`](IntcodeVM::extend_input))
+    #[inline]
+    pub fn inputs<I: IntoIterator<Item = T>>(mut self, inputs: I) -> Self {
+        self.inputs.extend(inputs);
+        self
+    }
+
+    /// See [`IntcodeVM::set_max_memory()`](IntcodeVM::set_max_memory)
+    #[inline]
+    pub fn max_memory(mut self, max: usize) -> Self {
+        self.max_memory = Some(max);
+        self
+    }
+
+    /// See [`IntcodeVM::set_strict_memory()`](IntcodeVM::set_strict_memory)
+    #[inline]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// See [`IntcodeVM::set_code_limit()`](IntcodeVM::set_code_limit)
+    #[inline]
+    pub fn code_limit(mut self, limit: usize) -> Self {
+        self.code_limit = Some(limit);
+        self
+    }
+
+    /// See [`IntcodeVM::set_instruction_limit()`](IntcodeVM::set_instruction_limit)
+    #[inline]
+    pub fn instruction_limit(mut self, limit: u64) -> Self {
+        self.instruction_limit = Some(limit);
+        self
+    }
+
+    /// See [`IntcodeVM::set_fuel()`](IntcodeVM::set_fuel)
+    #[inline]
+    pub fn fuel(mut self, fuel: u64) -> Self {
+        self.fuel = Some(fuel);
+        self
+    }
+
+    /// See [`IntcodeVM::set_halt_behavior()`](IntcodeVM::set_halt_behavior)
+    #[inline]
+    pub fn halt_behavior(mut self, behavior: HaltBehavior) -> Self {
+        self.halt_behavior = behavior;
+        self
+    }
+
+    /// See [`IntcodeVM::set_input_on_empty()`](IntcodeVM::set_input_on_empty)
+    #[inline]
+    pub fn input_on_empty(mut self, behavior: EmptyInputBehavior<T>) -> Self {
+        self.input_on_empty = behavior;
+        self
+    }
+
+    /// Addresses of the breakpoints of the VM (see [`IntcodeVM::add_breakpoint()`](IntcodeVM::add_breakpoint))
+    #[inline]
+    pub fn breakpoints<I: IntoIterator<Item = usize>>(mut self, addresses: I) -> Self {
+        self.breakpoints.extend(addresses);
+        self
+    }
+
+    /// See [`IntcodeVM::set_buffer_output()`](IntcodeVM::set_buffer_output)
+    #[inline]
+    pub fn buffer_output(mut self, enabled: bool) -> Self {
+        self.buffer_output = enabled;
+        self
+    }
+
+    /// See [`IntcodeVM::enable_trace()`](IntcodeVM::enable_trace)
+    #[inline]
+    pub fn trace(mut self) -> Self {
+        self.trace = true;
+        self
+    }
+
+    /// See [`IntcodeVM::enable_access_profiling()`](IntcodeVM::enable_access_profiling)
+    #[inline]
+    pub fn access_profiling(mut self) -> Self {
+        self.access_profiling = true;
+        self
+    }
+
+    /// See [`IntcodeVM::enable_profiling()`](IntcodeVM::enable_profiling)
+    #[inline]
+    pub fn profiling(mut self) -> Self {
+        self.profiling = true;
+        self
+    }
+
+    /// See [`IntcodeVM::enable_input_logging()`](IntcodeVM::enable_input_logging)
+    #[inline]
+    pub fn input_logging(mut self) -> Self {
+        self.input_logging = true;
+        self
+    }
+
+    /// Creates the configured VM
+    pub fn build(self) -> IntcodeVM<T> {
+        let mut vm = IntcodeVM::new(self.memory);
+        vm.extend_input(self.inputs);
+        if let Some(max) = self.max_memory {
+            vm.set_max_memory(max);
+        }
+        vm.set_strict_memory(self.strict);
+        if let Some(limit) = self.code_limit {
+            vm.set_code_limit(limit);
+        }
+        if let Some(limit) = self.instruction_limit {
+            vm.set_instruction_limit(limit);
+        }
+        if let Some(fuel) = self.fuel {
+            vm.set_fuel(fuel);
+        }
+        vm.set_halt_behavior(self.halt_behavior);
+        vm.set_input_on_empty(self.input_on_empty);
+        for address in self.breakpoints {
+            vm.add_breakpoint(address);
+        }
+        vm.set_buffer_output(self.buffer_output);
+        if self.trace {
+            vm.enable_trace();
+        }
+        if self.access_profiling {
+            vm.enable_access_profiling();
+        }
+        if self.profiling {
+            vm.enable_profiling();
+        }
+        if self.input_logging {
+            vm.enable_input_logging();
+        }
+
+        vm
+    }
+}
+
+impl<T> IntcodeVM<T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    /// Creates a [builder](IntcodeVMBuilder) of a VM
+    #[inline]
+    pub fn builder() -> IntcodeVMBuilder<T> {
+        IntcodeVMBuilder::default()
+    }
+}
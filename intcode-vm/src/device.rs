@@ -0,0 +1,166 @@
+//! Pluggable input/output backends for the [VM](IntcodeVM)
+
+#[cfg(feature = "std")]
+use std::sync::mpsc::{Receiver, Sender};
+
+use alloc::collections::VecDeque;
+
+use num::{Integer, ToPrimitive};
+
+#[cfg(feature = "std")]
+use crate::error::VMError;
+use crate::{error, IntcodeVM, VMResult};
+
+/// Input/output backend of a VM (see [`IntcodeVM::run_with_device()`](IntcodeVM::run_with_device))
+pub trait IoDevice<T> {
+    /// Next input of the VM, [`None`] if there is none available yet
+    fn read(&mut self) -> Option<T>;
+
+    /// Called with every value outputted by the VM
+    fn write(&mut self, value: T);
+}
+
+/// [IoDevice] reading its inputs from a queue and storing the outputs in another one
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VecDeviceIo<T> {
+    inputs: VecDeque<T>,
+    outputs: VecDeque<T>,
+}
+
+impl<T> Default for VecDeviceIo<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            inputs: VecDeque::new(),
+            outputs: VecDeque::new(),
+        }
+    }
+}
+
+impl<T> VecDeviceIo<T> {
+    /// Creates a device providing `inputs` (in order) to the VM
+    #[inline]
+    pub fn new<I: IntoIterator<Item = T>>(inputs: I) -> Self {
+        Self {
+            inputs: inputs.into_iter().collect(),
+            outputs: VecDeque::new(),
+        }
+    }
+
+    /// Adds `value` after the inputs not yet read
+    #[inline]
+    pub fn push_input(&mut self, value: T) {
+        self.inputs.push_back(value);
+    }
+
+    /// Values written by the VM and not yet [popped](VecDeviceIo::pop_output), in output order
+    #[inline]
+    pub fn outputs(&self) -> &VecDeque<T> {
+        &self.outputs
+    }
+
+    /// Removes the oldest value written by the VM
+    #[inline]
+    pub fn pop_output(&mut self) -> Option<T> {
+        self.outputs.pop_front()
+    }
+}
+
+impl<T> IoDevice<T> for VecDeviceIo<T> {
+    #[inline]
+    fn read(&mut self) -> Option<T> {
+        self.inputs.pop_front()
+    }
+
+    #[inline]
+    fn write(&mut self, value: T) {
+        self.outputs.push_back(value);
+    }
+}
+
+impl<T> IntcodeVM<T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    /// Executes the program, reading the inputs from `device` and writing the outputs to it
+    ///
+    /// Returns [`VMResult::WaitingForInput`] when the program asks for an input
+    /// (and the input queue of the VM is empty) but `device` has none available,
+    /// otherwise returns the same results as [`vm.run()`](IntcodeVM::run) except [`VMResult::Output`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{device::VecDeviceIo, IntcodeVM, VMResult};
+    /// // outputs the sum of its 2 inputs
+    /// let mut vm = IntcodeVM::new([3, 11, 3, 12, 1, 11, 12, 11, 4, 11, 99, 0, 0]);
+    /// let mut device = VecDeviceIo::new([3]);
+    /// assert_eq!(vm.run_with_device(&mut device).unwrap(), VMResult::WaitingForInput);
+    ///
+    /// device.push_input(4);
+    /// assert_eq!(vm.run_with_device(&mut device).unwrap(), VMResult::Halted);
+    /// assert_eq!(device.pop_output(), Some(7));
+    /// ```
+    pub fn run_with_device<D: IoDevice<T>>(
+        &mut self,
+        device: &mut D,
+    ) -> error::Result<VMResult<T>, T> {
+        loop {
+            match self.run()? {
+                VMResult::WaitingForInput => match device.read() {
+                    Some(input) => {
+                        self.set_next_input(input);
+                    }
+                    None => return Ok(VMResult::WaitingForInput),
+                },
+                VMResult::Output(out) => device.write(out),
+                other => return Ok(other),
+            }
+        }
+    }
+
+    /// Runs the program until it halts, receiving its inputs from `input` (blocking until one is available)
+    /// and sending its outputs on `output`
+    ///
+    /// # Errors
+    ///
+    /// - [`VMError::InputDisconnected`] if the program asks for an input after every sender of `input` was dropped
+    /// - [`VMError::OutputDisconnected`] if the program outputs a value after `output`'s receiver was dropped
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::{sync::mpsc, thread};
+    /// # use intcode_vm::IntcodeVM;
+    /// // outputs its input times 3
+    /// let mut vm = IntcodeVM::new([3, 9, 1002, 9, 3, 9, 4, 9, 99, 0]);
+    /// let (input, input_receiver) = mpsc::channel();
+    /// let (output_sender, output) = mpsc::channel();
+    ///
+    /// let handle = thread::spawn(move || vm.run_with_channels(input_receiver, output_sender));
+    /// input.send(14).unwrap();
+    /// assert_eq!(output.recv().unwrap(), 42);
+    /// assert!(handle.join().unwrap().is_ok());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn run_with_channels(
+        &mut self,
+        input: Receiver<T>,
+        output: Sender<T>,
+    ) -> error::Result<(), T> {
+        loop {
+            match self.run()? {
+                VMResult::OutOfFuel => return Err(VMError::OutOfFuel),
+                VMResult::WaitingForInput => {
+                    let value = input.recv().map_err(|_| VMError::InputDisconnected)?;
+                    self.set_next_input(value);
+                }
+                VMResult::Output(out) => output
+                    .send(out)
+                    .map_err(|err| VMError::OutputDisconnected(err.0))?,
+                VMResult::BreakpointHit(_) | VMResult::WatchpointHit { .. } => (),
+                _ => return Ok(()),
+            }
+        }
+    }
+}
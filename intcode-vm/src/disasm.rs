@@ -0,0 +1,151 @@
+use core::fmt::Display;
+
+use alloc::{collections::BTreeSet, format, string::String, vec::Vec};
+
+use num::{Integer, ToPrimitive};
+
+use crate::{
+    error,
+    memory::Memory,
+    vm::instr::{self, Instruction},
+};
+
+/// Instruction decoded without being executed (see [`IntcodeVM::decode_at()`](crate::IntcodeVM::decode_at))
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DecodedInstruction<T> {
+    /// Address of the instruction
+    pub address: usize,
+    /// Operation of the instruction, without the argument modes
+    pub opcode: u16,
+    /// Mode (0 positional, 1 immediate, 2 relative) and raw value of each argument, in order
+    pub operands: Vec<(u8, T)>,
+}
+
+impl<T> DecodedInstruction<T> {
+    /// Number of cells taken by the instruction (the address of the next one being `address + width`)
+    #[inline]
+    pub fn width(&self) -> usize {
+        1 + self.operands.len()
+    }
+}
+
+/// Decodes `memory` linearly from address 0, producing a line per instruction
+///
+/// Arguments are written `[5]` in positional mode, `#5` in immediate mode and `[~5]` in relative mode,
+/// the address an instruction writes to is written `&5` (or `&~5` in relative mode).
+/// Cells that cannot be decoded are listed one by one as `DATA`.
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::{disasm::disassemble, memory::Memory};
+/// let memory = Memory::from([1001, 4, 3, 5, 204, -1, 99, 123]);
+///
+/// assert_eq!(
+///     disassemble(&memory),
+///     [
+///         "0: ADD [4] #3 -> &5",
+///         "4: OUT [~-1]",
+///         "6: HALT",
+///         "7: DATA 123",
+///     ]
+/// );
+/// ```
+pub fn disassemble<T>(memory: &Memory<T>) -> Vec<String>
+where
+    T: Integer + Clone + ToPrimitive + Display,
+{
+    let mut lines = Vec::new();
+    let mut address = 0;
+    while address < memory.len() {
+        match Instruction::decode(memory, address) {
+            Ok(instruction) => {
+                lines.push(format!("{address}: {instruction}"));
+                address += instruction.instruction_width();
+            }
+            Err(_) => {
+                lines.push(format!("{address}: DATA {}", memory[address]));
+                address += 1;
+            }
+        }
+    }
+
+    lines
+}
+
+/// Opcodes (without the argument modes) of the instructions decoded linearly from address 0
+/// (like [disassemble]), without running the program
+///
+/// The scan stops at the first cell that cannot be decoded as an instruction (typically the start of a data region),
+/// the instructions after it are not part of the set.
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::{disasm::scan_opcodes, memory::Memory};
+/// let memory = Memory::from([1001, 4, 3, 5, 204, -1, 99, 0, 3, 0]);
+/// assert_eq!(scan_opcodes(&memory).into_iter().collect::<Vec<_>>(), [1, 4, 99]);
+/// ```
+pub fn scan_opcodes<T>(memory: &Memory<T>) -> BTreeSet<u16>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    let mut opcodes = BTreeSet::new();
+    let mut address = 0;
+    while address < memory.len() {
+        let Ok(instruction) = Instruction::decode(memory, address) else {
+            break;
+        };
+
+        opcodes.insert(instruction.opcode());
+        address += instruction.instruction_width();
+    }
+
+    opcodes
+}
+
+/// Checks that every instruction statically reachable from address 0 decodes,
+/// without running the program
+///
+/// This is a best-effort check: the execution flow is followed through straight-line code and the jumps whose
+/// target is an immediate value (both ways for a conditional jump, unless its condition is immediate too),
+/// the jumps depending on the content of the memory cannot be followed.
+/// The exploration stops at halt instructions.
+///
+/// # Errors
+///
+/// Returns the decoding error (such as [`VMError::UnknownInstruction`](error::VMError::UnknownInstruction))
+/// of the reachable instruction at the lowest address, if any
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::{disasm::validate, error::VMError, memory::Memory};
+/// assert!(validate(&Memory::from([1101, 2, 3, 7, 4, 7, 99, 0])).is_ok());
+///
+/// assert!(matches!(
+///     validate(&Memory::from([15])),
+///     Err(VMError::UnknownInstruction { opcode: 15, address: 0 })
+/// ));
+///
+/// // always jumps to 4, the cells after the halts are never reached
+/// assert!(validate(&Memory::from([1105, 1, 4, 15, 99, 15])).is_ok());
+///
+/// // may jump to 5, depending on the cell at address 7
+/// assert!(matches!(
+///     validate(&Memory::from([1005, 7, 5, 99, 0, 15, 99, 0])),
+///     Err(VMError::UnknownInstruction { opcode: 15, address: 5 })
+/// ));
+/// ```
+pub fn validate<T>(memory: &Memory<T>) -> error::Result<(), T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    match instr::reachable_instructions(memory, 0)
+        .into_values()
+        .find_map(Result::err)
+    {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
@@ -1,32 +1,291 @@
+use core::{error::Error, fmt};
+
+use alloc::{string::String, vec::Vec};
+
 use num::{Integer, ToPrimitive};
-use thiserror::Error;
 
-/// [Error](std::error::Error) type returned by the [VM](crate::vm::IntcodeVM)
-#[derive(Error, Debug)]
+/// [Error] type returned by the [VM](crate::vm::IntcodeVM)
+///
+/// Each variant is displayed as a sentence naming the offending values (opcode, address, value...),
+/// none of them wraps another error (its [`source()`](Error::source) is always [`None`]).
+/// The errors of the parsing functions are separate types, whose source is the error of the value that failed to parse.
+#[derive(Debug)]
 pub enum VMError<T>
 where
     T: Integer + Clone + ToPrimitive,
 {
-    #[error("The instruction {0} was not any of [01, 02, 03, 04, 05, 06, 07, 08, 09, 99]")]
-    UnknownInstruction(u16),
+    /// The opcode of the instruction at `address` is not a known instruction
+    UnknownInstruction { opcode: u16, address: usize },
 
-    #[error("Could not cast {0} to u16 (opcode is cast to u16 before being parsed)")]
-    CannotCastToU16(T),
+    /// The `value` at `address` is executed as an instruction but does not fit in a [u16]
+    CannotCastToU16 { value: T, address: usize },
 
-    #[error("Could not cast {0} to usize (address is cast to usize before being used)")]
+    /// The value used as an address is negative
     CannotCastToUsize(T),
 
-    #[error("The argument mode in opcode {opcode} for argument n°{arg_num} is not recognized (was {arg_mode}, should be 0, 1 or 2)")]
+    /// The value used as an address is larger than [`usize::MAX`]
+    AddressTooLarge(T),
+
+    /// The mode of argument n°`arg_num` (starting at 1) of the instruction at `address` is not 0, 1 or 2
     InvalidArgMode {
         opcode: u16,
         arg_num: u8,
         arg_mode: u8,
+        address: usize,
+    },
+
+    /// Argument n°`arg_num` (starting at 1) of the instruction at `address` is written to but is in immediate mode
+    ArgModeCannotBeImmediate {
+        opcode: u16,
+        arg_num: u8,
+        address: usize,
+    },
+
+    /// The input instruction at `address` stores its input in immediate mode
+    InputTargetImmediate { opcode: u16, address: usize },
+
+    /// The program outputted a value that is not an ASCII code while ASCII was expected
+    InvalidAsciiOutput(T),
+
+    /// An arcade game outputted an invalid tile ID, coordinate or score
+    /// (see [`run_arcade()`](crate::games::run_arcade))
+    InvalidArcadeOutput(T),
+
+    /// A painting robot outputted a color or a turn that is neither 0 nor 1
+    /// (see [`run_painting_robot()`](crate::games::run_painting_robot))
+    InvalidRobotOutput(T),
+
+    /// The character given as ASCII input is not ASCII
+    NonAsciiInput(char),
+
+    /// The program asked for an input that a helper running it does not provide
+    UnexpectedInputRequest,
+
+    /// The program halted after producing only `collected` of the outputs expected
+    HaltedBeforeOutput { collected: usize },
+
+    /// The jump instruction at `from` lands on `target`, which is not the start of an instruction (alignment check)
+    MisalignedJump { from: usize, target: usize },
+
+    /// Output n°`index` (starting at 0) differs from the expected one, [`None`] standing for a missing output on either side
+    OutputMismatch {
+        index: usize,
+        expected: Option<T>,
+        actual: Option<T>,
+    },
+
+    /// The program stopped without failing, there is no error to reproduce
+    NoErrorToReproduce,
+
+    /// Every VM of a pipeline still running (`waiting` holds their indices) waits for an input that will never come
+    PipelineDeadlock { waiting: Vec<usize> },
+
+    /// The instruction limit was reached, `executed` being the total number of instructions executed by the VM
+    InstructionLimitExceeded { executed: u64 },
+
+    /// A helper ran the VM out of fuel before the program stopped
+    OutOfFuel,
+
+    /// The program wrote at `address`, past the `limit` on the number of cells of the memory
+    MemoryLimitExceeded { address: usize, limit: usize },
+
+    /// The program read the address past the end of the memory, with strict memory enabled
+    AddressOutOfBounds(usize),
+
+    /// The instruction pointer left the code region, its value being held
+    InstructionPointerOutOfRange(usize),
+
+    /// The addition or multiplication at `ip` (opcode `opcode`) overflowed, with checked arithmetic enabled
+    ArithmeticOverflow { opcode: u16, ip: usize },
+
+    /// The program asked for an input while the input channel is disconnected
+    InputDisconnected,
+
+    /// The program outputted a value while the output channel is disconnected
+    OutputDisconnected(T),
+
+    /// The program halted with `leftover` outputs left over, not enough for a chunk of `size` outputs
+    IncompleteOutputChunk { size: usize, leftover: usize },
+
+    /// Diagnostic test n°`at` (the index of its output, starting at 0) outputted `value` instead of 0
+    DiagnosticFailed { at: usize, value: T },
+}
+
+impl<T> fmt::Display for VMError<T>
+where
+    T: Integer + Clone + ToPrimitive + fmt::Display + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownInstruction { opcode, address } => write!(f, "The instruction {opcode} at address {address} was not any of [01, 02, 03, 04, 05, 06, 07, 08, 09, 99]"),
+            Self::CannotCastToU16 { value, address } => write!(f, "The value {value} at address {address} is not an instruction, it does not fit in a u16 (opcode is cast to u16 before being parsed)"),
+            Self::CannotCastToUsize(value) => write!(f, "Could not cast {value} to usize (address is cast to usize before being used)"),
+            Self::AddressTooLarge(value) => write!(f, "{value} is too large to be an address (addresses go up to {})", usize::MAX),
+            Self::InvalidArgMode { opcode, arg_num, arg_mode, address } => write!(f, "The argument mode in opcode {opcode} (at address {address}) for argument n°{arg_num} is not recognized (was {arg_mode}, should be 0, 1 or 2)"),
+            Self::ArgModeCannotBeImmediate { opcode, arg_num, address } => write!(f, "The argument mode in opcode {opcode} (at address {address}) for argument n°{arg_num} cannot be immediate (1)"),
+            Self::InputTargetImmediate { opcode, address } => write!(f, "The input instruction {opcode} at address {address} cannot store its input in immediate mode (1)"),
+            Self::InvalidAsciiOutput(value) => write!(f, "Expected an ASCII code as output, got {value}"),
+            Self::InvalidRobotOutput(value) => write!(f, "Expected 0 or 1 as output (a color or a turn), got {value}"),
+            Self::InvalidArcadeOutput(value) => write!(f, "Expected a tile ID (0 to 4), or a coordinate or a score fitting in an i64 as output, got {value}"),
+            Self::NonAsciiInput(c) => write!(f, "Cannot input {c:?}, it is not an ASCII character"),
+            Self::UnexpectedInputRequest => f.write_str("The VM requested an input but none was provided"),
+            Self::HaltedBeforeOutput { collected } => write!(f, "The VM halted after producing only {collected} output(s)"),
+            Self::MisalignedJump { from, target } => write!(f, "The jump at {from} lands on {target}, which is not the start of an instruction"),
+            Self::OutputMismatch { index, expected, actual } => write!(f, "Output n°{index} was {actual:?}, expected {expected:?}"),
+            Self::NoErrorToReproduce => f.write_str("The program stopped without running into an error"),
+            Self::PipelineDeadlock { waiting } => write!(f, "Every running VM of the pipeline is waiting for an input that will never come (VMs {waiting:?})"),
+            Self::InstructionLimitExceeded { executed } => write!(f, "The VM executed {executed} instructions without stopping"),
+            Self::OutOfFuel => f.write_str("The VM ran out of fuel before the program stopped"),
+            Self::MemoryLimitExceeded { address, limit } => write!(f, "Cannot write at address {address}, the memory is limited to {limit} cells"),
+            Self::AddressOutOfBounds(address) => write!(f, "Cannot read address {address}, it is past the end of the memory (strict memory is enabled)"),
+            Self::InstructionPointerOutOfRange(ip) => write!(f, "The instruction pointer {ip} is past the end of the code region"),
+            Self::ArithmeticOverflow { opcode, ip } => write!(f, "The instruction {opcode} at address {ip} overflowed (checked arithmetic is enabled)"),
+            Self::InputDisconnected => f.write_str("The VM requested an input but the input channel is disconnected"),
+            Self::OutputDisconnected(value) => write!(f, "The VM outputted {value} but the output channel is disconnected"),
+            Self::IncompleteOutputChunk { size, leftover } => write!(f, "The VM halted with {leftover} output(s) left over, not enough for a chunk of {size}"),
+            Self::DiagnosticFailed { at, value } => write!(f, "Diagnostic test n°{at} failed (output was {value}, expected 0)"),
+        }
+    }
+}
+
+impl<T> Error for VMError<T> where T: Integer + Clone + ToPrimitive + fmt::Display + fmt::Debug {}
+
+pub type Result<T, I> = core::result::Result<T, VMError<I>>;
+
+/// Error returned when one of several programs could not be parsed
+/// ([`IntcodeVM::parse_many()`](crate::vm::IntcodeVM::parse_many))
+#[derive(Debug)]
+pub struct ParseError<E> {
+    /// Line of the program (starting at 1)
+    pub line: usize,
+    pub source: E,
+}
+
+impl<E: fmt::Display> fmt::Display for ParseError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Could not parse the program at line {}: {}",
+            self.line, self.source
+        )
+    }
+}
+
+impl<E: Error + 'static> Error for ParseError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Error returned by [`Memory::parse_with_overflow_hint()`](crate::memory::Memory::parse_with_overflow_hint)
+/// and [`Memory::from_str_radix()`](crate::memory::Memory::from_str_radix)
+#[derive(Debug)]
+pub enum MemoryParseError<E> {
+    Invalid {
+        token: String,
+        source: E,
+    },
+
+    Overflow {
+        token: String,
+        type_name: &'static str,
+        suggested_type: &'static str,
     },
+}
+
+impl<E: fmt::Display> fmt::Display for MemoryParseError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid { token, source } => write!(f, "Could not parse {token:?}: {source}"),
+            Self::Overflow {
+                token,
+                type_name,
+                suggested_type,
+            } => write!(
+                f,
+                "value {token} overflows {type_name}; consider {suggested_type}"
+            ),
+        }
+    }
+}
 
-    #[error(
-        "The argument mode in opcode {opcode} for argument n°{arg_num} cannot be immediate (1)"
-    )]
-    ArgModeCannotBeImmediate { opcode: u16, arg_num: u8 },
+impl<E: Error + 'static> Error for MemoryParseError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Invalid { source, .. } => Some(source),
+            Self::Overflow { .. } => None,
+        }
+    }
 }
 
-pub type Result<T, I> = std::result::Result<T, VMError<I>>;
+/// Error returned when parsing a program from bytes
+/// ([`Memory::parse_bytes()`](crate::memory::Memory::parse_bytes))
+#[derive(Debug)]
+pub enum FromBytesError<E> {
+    Utf8(core::str::Utf8Error),
+
+    Parse(E),
+}
+
+impl<E> From<core::str::Utf8Error> for FromBytesError<E> {
+    #[inline]
+    fn from(value: core::str::Utf8Error) -> Self {
+        Self::Utf8(value)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for FromBytesError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Utf8(err) => write!(f, "The program is not valid UTF-8: {err}"),
+            Self::Parse(err) => write!(f, "Could not parse the program: {err}"),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for FromBytesError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Utf8(err) => Some(err),
+            Self::Parse(err) => Some(err),
+        }
+    }
+}
+
+/// Error returned when loading a program from a file
+/// ([`Memory::from_file()`](crate::memory::Memory::from_file))
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum FromFileError<E> {
+    Io(std::io::Error),
+
+    Parse(E),
+}
+
+#[cfg(feature = "std")]
+impl<E> From<std::io::Error> for FromFileError<E> {
+    #[inline]
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Display> fmt::Display for FromFileError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "Could not read the program file: {err}"),
+            Self::Parse(err) => write!(f, "Could not parse the program: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Error + 'static> Error for FromFileError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse(err) => Some(err),
+        }
+    }
+}
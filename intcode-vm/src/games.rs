@@ -0,0 +1,220 @@
+//! Helpers interpreting the outputs of game programs (such as the arcade cabinet of day 13
+//! or the painting robot of day 11)
+
+use alloc::collections::BTreeMap;
+
+use num::{Integer, ToPrimitive};
+
+use crate::{
+    error::{self, VMError},
+    memory::Memory,
+    IntcodeVM, VMResult,
+};
+
+/// Tile drawn on the screen of an [Arcade]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Tile {
+    /// Tile ID 0
+    #[default]
+    Empty,
+    /// Tile ID 1, indestructible
+    Wall,
+    /// Tile ID 2, broken by the ball
+    Block,
+    /// Tile ID 3, the horizontal paddle
+    Paddle,
+    /// Tile ID 4, the ball
+    Ball,
+}
+
+impl Tile {
+    /// Tile with the ID `id`, [`None`] if it is not in the range from 0 to 4
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::games::Tile;
+    /// assert_eq!(Tile::from_id(2), Some(Tile::Block));
+    /// assert_eq!(Tile::from_id(5), None);
+    /// ```
+    #[inline]
+    pub const fn from_id(id: i64) -> Option<Self> {
+        Some(match id {
+            0 => Self::Empty,
+            1 => Self::Wall,
+            2 => Self::Block,
+            3 => Self::Paddle,
+            4 => Self::Ball,
+            _ => return None,
+        })
+    }
+}
+
+/// Screen (and score) drawn by an arcade game (see [run_arcade])
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Arcade {
+    screen: BTreeMap<(i64, i64), Tile>,
+    score: Option<i64>,
+}
+
+impl Arcade {
+    /// Last tile drawn at each `(x, y)` position
+    #[inline]
+    pub fn screen(&self) -> &BTreeMap<(i64, i64), Tile> {
+        &self.screen
+    }
+
+    /// Tile at `(x, y)`, [`Tile::Empty`] if nothing was drawn there
+    #[inline]
+    pub fn tile(&self, x: i64, y: i64) -> Tile {
+        self.screen.get(&(x, y)).copied().unwrap_or_default()
+    }
+
+    /// Number of positions showing `tile`
+    #[inline]
+    pub fn count(&self, tile: Tile) -> usize {
+        self.screen.values().filter(|&&drawn| drawn == tile).count()
+    }
+
+    /// Last score displayed (by an output at `(-1, 0)`), [`None`] if the game never displayed one
+    #[inline]
+    pub const fn score(&self) -> Option<i64> {
+        self.score
+    }
+}
+
+/// Runs an arcade game to halt, interpreting its outputs as `x, y, tile` triples drawn on a screen
+///
+/// A triple at `(-1, 0)` displays a score instead of a tile.
+///
+/// # Errors
+///
+/// - [`VMError::InvalidArcadeOutput`] if a tile ID is not in the range from 0 to 4,
+///   or if a coordinate or a score does not fit in an [`i64`]
+/// - [`VMError::IncompleteOutputChunk`] if the number of outputs is not a multiple of 3
+/// - [`VMError::UnexpectedInputRequest`] if the game asks for an input (a joystick move)
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::{games::{run_arcade, Tile}, memory::Memory};
+/// let program = Memory::from([104, 1, 104, 2, 104, 3, 104, 6, 104, 5, 104, 4, 104, -1, 104, 0, 104, 12, 99]);
+/// let arcade = run_arcade(program).unwrap();
+///
+/// assert_eq!(arcade.tile(1, 2), Tile::Paddle);
+/// assert_eq!(arcade.tile(6, 5), Tile::Ball);
+/// assert_eq!(arcade.tile(0, 0), Tile::Empty);
+/// assert_eq!(arcade.score(), Some(12));
+/// ```
+pub fn run_arcade<T>(program: Memory<T>) -> error::Result<Arcade, T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    let to_i64 = |value: T| value.to_i64().ok_or(VMError::InvalidArcadeOutput(value));
+
+    let mut arcade = Arcade::default();
+    for [x, y, id] in IntcodeVM::new(program).collect_output_chunks::<3>()? {
+        let position = (to_i64(x)?, to_i64(y)?);
+        if position == (-1, 0) {
+            arcade.score = Some(to_i64(id)?);
+            continue;
+        }
+
+        let tile = id
+            .to_i64()
+            .and_then(Tile::from_id)
+            .ok_or(VMError::InvalidArcadeOutput(id))?;
+        arcade.screen.insert(position, tile);
+    }
+
+    Ok(arcade)
+}
+
+/// Runs the brain of a painting robot to halt, the robot moving on a grid of panels
+/// (all black but the starting one, which is white if `start_white` is `true`)
+///
+/// Before each step, the color of the panel the robot is on is given as input (0 for black, 1 for white).
+/// The robot then paints the panel with the color of the first output and turns with the second one
+/// (0 for left, 1 for right) before moving forward by one panel. It starts at `(0, 0)` facing up,
+/// `y` growing downwards like on a screen.
+///
+/// Returns the color of every panel painted at least once (`true` for white)
+///
+/// # Errors
+///
+/// - [`VMError::InvalidRobotOutput`] if a color or a turn is neither 0 nor 1
+/// - [`VMError::UnexpectedInputRequest`] if the program asks for an input between a color and a turn
+/// - [`VMError::IncompleteOutputChunk`] if the program halts after a color without a turn
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::{games::run_painting_robot, memory::Memory};
+/// // paints white and turns right, then paints black and turns left
+/// let program = Memory::from([3, 100, 104, 1, 104, 1, 3, 100, 104, 0, 104, 0, 99]);
+/// let panels = run_painting_robot(program, false).unwrap();
+///
+/// assert_eq!(panels.get(&(0, 0)), Some(&true));
+/// assert_eq!(panels.get(&(1, 0)), Some(&false));
+/// assert_eq!(panels.len(), 2);
+/// ```
+pub fn run_painting_robot<T>(
+    program: Memory<T>,
+    start_white: bool,
+) -> error::Result<BTreeMap<(i64, i64), bool>, T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    let to_bool = |value: T| {
+        if value.is_zero() {
+            Ok(false)
+        } else if value.is_one() {
+            Ok(true)
+        } else {
+            Err(VMError::InvalidRobotOutput(value))
+        }
+    };
+
+    let mut vm = IntcodeVM::new(program);
+    let mut panels = BTreeMap::new();
+    let mut position = (0i64, 0i64);
+    let mut direction = (0i64, -1i64);
+    let mut paint = None;
+    loop {
+        match vm.run()? {
+            VMResult::WaitingForInput if paint.is_some() => {
+                return Err(VMError::UnexpectedInputRequest)
+            }
+            VMResult::WaitingForInput => {
+                let white = panels
+                    .get(&position)
+                    .copied()
+                    .unwrap_or(start_white && position == (0, 0));
+                vm.set_next_input(if white { T::one() } else { T::zero() });
+            }
+            VMResult::Output(out) => match paint.take() {
+                None => paint = Some(to_bool(out)?),
+                Some(white) => {
+                    panels.insert(position, white);
+                    direction = if to_bool(out)? {
+                        (-direction.1, direction.0)
+                    } else {
+                        (direction.1, -direction.0)
+                    };
+                    position = (position.0 + direction.0, position.1 + direction.1);
+                }
+            },
+            VMResult::OutOfFuel => return Err(VMError::OutOfFuel),
+            VMResult::BreakpointHit(_) | VMResult::WatchpointHit { .. } => (),
+            _ => break,
+        }
+    }
+
+    match paint {
+        Some(_) => Err(VMError::IncompleteOutputChunk {
+            size: 2,
+            leftover: 1,
+        }),
+        None => Ok(panels),
+    }
+}
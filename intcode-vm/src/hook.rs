@@ -0,0 +1,44 @@
+use core::fmt;
+
+use alloc::boxed::Box;
+
+/// Optional callback (or input source) stored in a [VM](crate::IntcodeVM)
+///
+/// Boxed trait objects cannot be cloned: a clone of a hook is always empty.
+pub(crate) struct Hook<F: ?Sized>(Option<Box<F>>);
+
+impl<F: ?Sized> Hook<F> {
+    #[inline]
+    pub(crate) fn set(&mut self, callback: Box<F>) {
+        self.0 = Some(callback);
+    }
+
+    #[inline]
+    pub(crate) fn get_mut(&mut self) -> Option<&mut F> {
+        self.0.as_deref_mut()
+    }
+}
+
+impl<F: ?Sized> Default for Hook<F> {
+    #[inline]
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<F: ?Sized> Clone for Hook<F> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(None)
+    }
+}
+
+impl<F: ?Sized> fmt::Debug for Hook<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() {
+            "Hook(Some(..))"
+        } else {
+            "Hook(None)"
+        })
+    }
+}
@@ -27,17 +27,88 @@
 //!     30, 40, 50
 //! ]))
 //! ```
+//!
+//! # Arithmetic
+//!
+//! Additions and multiplications are the ones of the VM's value type `T`: with a primitive integer
+//! they panic on overflow in debug builds and wrap around in release builds,
+//! while a big integer type never overflows.
+//! To get the same results in every build, use a [WrappingVM] (wrapping around on overflow of an [`i64`])
+//! or [`IntcodeVM::set_checked_arithmetic()`](vm::IntcodeVM::set_checked_arithmetic)
+//! (returning an error on overflow).
+//!
+//! # Big integers
+//!
+//! Programs whose values do not fit in an [`i64`] can be run with [`BigInt`](num::BigInt) values:
+//!
+//! ```
+//! # use intcode_vm::{IntcodeVM, VMResult};
+//! use num::BigInt;
+//!
+//! // outputs 2^64 * 2^64
+//! let mut vm: IntcodeVM<BigInt> = "1102,18446744073709551616,18446744073709551616,7,4,7,99,0".parse().unwrap();
+//! let expected: BigInt = "340282366920938463463374607431768211456".parse().unwrap();
+//! assert_eq!(vm.run().unwrap(), VMResult::Output(expected));
+//! ```
+//!
+//! Addresses (and jump targets) must still fit in a [`usize`], a bigger value makes the VM
+//! return [`VMError::AddressTooLarge`](error::VMError::AddressTooLarge).
+//! Every cell of a big integer memory is a separate allocation and every operation clones its operands,
+//! running the same program is several times slower than with a primitive integer type.
+//!
+//! # `no_std`
+//!
+//! The crate is `no_std` (relying on `alloc`) when its default `std` feature is disabled.
+//! Executing programs, [memories](memory::Memory) and the errors work the same,
+//! the following APIs are only available with the `std` feature:
+//!
+//! - reading programs from files and readers ([`Memory::from_file()`](memory::Memory::from_file),
+//!   [`Memory::from_reader()`](memory::Memory::from_reader), [`IntcodeVM::from_file()`](vm::IntcodeVM::from_file))
+//!   and [`FromFileError`](error::FromFileError)
+//! - the binary trace format ([`Trace::write_binary()`](trace::Trace::write_binary),
+//!   [`Trace::read_binary()`](trace::Trace::read_binary), [`IntcodeVM::write_trace_binary()`](vm::IntcodeVM::write_trace_binary))
+//! - running a VM over channels ([`IntcodeVM::run_with_channels()`](vm::IntcodeVM::run_with_channels))
+//! - the `rayon` and `serde` features, which enable `std`
+//!
+//! # Logging
+//!
+//! With the `log` feature, the VM emits [`log`](https://docs.rs/log) messages (it does nothing more without it):
+//!
+//! - `ip=4 opcode=1 operands=[30, 40, @3]` at the `trace` level before each instruction,
+//!   with the values its arguments read and `@address` for the cell it writes to
+//! - `ip=12 input=5`, `ip=12 waiting for input`, `ip=14 output=7` and `ip=16 halt` at the `debug` level
+//!
+//! Values that do not fit in an [`i128`] are written as `<big>`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+extern crate alloc;
+
+pub mod ascii;
+pub mod builder;
+pub mod device;
+pub mod disasm;
 pub mod error;
+pub mod games;
+mod hook;
 pub mod memory;
+pub mod network;
+pub mod search;
+#[cfg(feature = "serde")]
+mod serialization;
+pub mod trace;
+pub mod view;
 pub mod vm;
+pub mod wrapping;
 
 pub use vm::IntcodeVM;
 pub use vm::VMResult;
+pub use wrapping::WrappingVM;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use error::VMError;
 
     #[test]
     fn test_add() {
@@ -127,4 +198,1599 @@ mod tests {
         assert_eq!(vm.run().unwrap(), VMResult::Output(1125899906842624));
         assert_eq!(vm.run().unwrap(), VMResult::Halted);
     }
+
+    #[test]
+    fn test_diagnostic_reports_failing_test() {
+        let mut vm = IntcodeVM::new([104, 0, 104, 3, 3, 9, 4, 9, 99, 0]);
+        assert!(matches!(
+            vm.run_diagnostic(1),
+            Err(VMError::DiagnosticFailed { at: 1, value: 3 })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_trace_binary_round_trip() {
+        let prog = [
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        let mut vm = IntcodeVM::from(prog.iter().copied());
+        vm.enable_trace();
+        while vm.run().unwrap() != VMResult::Halted {}
+
+        let mut bytes = Vec::new();
+        vm.write_trace_binary(&mut bytes).unwrap();
+        let trace = trace::Trace::<i64>::read_binary(bytes.as_slice()).unwrap();
+        assert_eq!(trace.outputs(), &prog);
+        assert_eq!(Some(&trace), vm.trace());
+
+        // one byte per instruction pointer delta, rather than one `usize` each
+        assert!(bytes.len() < trace.instruction_pointers().len() * 2 + prog.len() * 2 + 16);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_trace_binary_rejects_other_data() {
+        let err = trace::Trace::<i64>::read_binary(&b"1,0,0,3,99"[..]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        let vm = IntcodeVM::new([99]);
+        assert!(vm.write_trace_binary(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_access_profile_counts_jump_target_only_when_jumping() {
+        let mut vm = IntcodeVM::new([6, 6, 7, 99, 99, 99, 0, 4]);
+        vm.enable_access_profiling();
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+        assert_eq!(vm.access_profile()[&6], (1, 0));
+        assert_eq!(vm.access_profile()[&7], (1, 0));
+
+        let mut vm = IntcodeVM::new([6, 6, 7, 99, 99, 99, 1, 4]);
+        vm.enable_access_profiling();
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+        assert_eq!(vm.access_profile()[&6], (1, 0));
+        assert!(!vm.access_profile().contains_key(&7));
+    }
+
+    #[test]
+    fn test_access_profile_disabled_by_default() {
+        let mut vm = IntcodeVM::new([1, 5, 5, 5, 99, 21]);
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+        assert!(vm.access_profile().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_from_file() {
+        let path = std::env::temp_dir().join("intcode_vm_test_from_file.txt");
+        std::fs::write(&path, "1,0,0,3,99\n").unwrap();
+        let mut vm = IntcodeVM::<i64>::from_file(&path).unwrap();
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+        assert!(vm.into_memory().memory_starts_with(&[1, 0, 0, 2, 99]));
+
+        std::fs::write(&path, "1,0,zero,3,99").unwrap();
+        let err = IntcodeVM::<i64>::from_file(&path).unwrap_err();
+        assert!(matches!(err, error::FromFileError::Parse(_)));
+        std::fs::remove_file(&path).unwrap();
+
+        let err = IntcodeVM::<i64>::from_file(&path).unwrap_err();
+        assert!(matches!(err, error::FromFileError::Io(_)));
+    }
+
+    #[test]
+    fn test_alignment_check_accepts_aligned_loops() {
+        let prog = [
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        let mut vm = IntcodeVM::from(prog.iter().copied());
+        vm.enable_alignment_check();
+        for num in prog {
+            assert_eq!(vm.run().unwrap(), VMResult::Output(num));
+        }
+
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    }
+
+    #[test]
+    fn test_minimal_repro_keeps_failing_operands() {
+        let vm = IntcodeVM::new([6, 7, 8, 99, 42, 42, 42, 0, -1, 42]);
+        let repro = vm.minimal_repro().unwrap();
+        assert_eq!(repro.iter().count(), 9);
+        assert!(repro.memory_starts_with(&[6, 7, 8, 0, 0, 0, 0, 0, -1]));
+        assert!(matches!(
+            IntcodeVM::new(repro).run(),
+            Err(VMError::CannotCastToUsize(-1))
+        ));
+
+        let vm = IntcodeVM::new([1, 0, 0, 0, 99]);
+        assert!(matches!(
+            vm.minimal_repro(),
+            Err(VMError::NoErrorToReproduce)
+        ));
+
+        // loops forever
+        let mut vm = IntcodeVM::new([1105, 1, 0]);
+        vm.set_instruction_limit(100);
+        assert!(matches!(
+            vm.minimal_repro(),
+            Err(VMError::NoErrorToReproduce)
+        ));
+        let mut vm = IntcodeVM::new([1105, 1, 0]);
+        vm.set_fuel(100);
+        assert!(matches!(
+            vm.minimal_repro(),
+            Err(VMError::NoErrorToReproduce)
+        ));
+    }
+
+    #[test]
+    fn test_minimal_repro_keeps_fill() {
+        let vm = IntcodeVM::new(memory::Memory::with_fill(vec![1, 10, 11, 12, 15], -1));
+        let repro = vm.minimal_repro().unwrap();
+        assert_eq!(repro.fill(), &-1);
+        assert!(repro.memory_starts_with(&[1, 10, 11, 12, 15, -1, -1, -1, -1, -1, -1, -1, -1]));
+        assert_eq!(repro.get(20), &-1);
+        assert!(matches!(
+            IntcodeVM::new(repro).run(),
+            Err(VMError::UnknownInstruction { .. })
+        ));
+    }
+
+    #[test]
+    fn test_run_expect_output_count() {
+        let program = [104, 1, 104, 2, 99];
+        assert!(matches!(
+            IntcodeVM::new(program).run_expect(&[], &[1, 2, 3]),
+            Err(VMError::OutputMismatch {
+                index: 2,
+                expected: Some(3),
+                actual: None
+            })
+        ));
+        assert!(matches!(
+            IntcodeVM::new(program).run_expect(&[], &[1]),
+            Err(VMError::OutputMismatch {
+                index: 1,
+                expected: None,
+                actual: Some(2)
+            })
+        ));
+        assert!(matches!(
+            IntcodeVM::new([3, 0, 99]).run_expect(&[], &[]),
+            Err(VMError::UnexpectedInputRequest)
+        ));
+    }
+
+    #[test]
+    fn test_overflow_hint_suggests_big_int() {
+        let err = memory::Memory::<i64>::parse_with_overflow_hint(
+            "1,-99999999999999999999999999999999999999999",
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            error::MemoryParseError::Overflow {
+                suggested_type: "num::BigInt",
+                ..
+            }
+        ));
+
+        let memory = memory::Memory::<i64>::parse_with_overflow_hint("1,0,0,3,99").unwrap();
+        assert!(memory.memory_starts_with(&[1, 0, 0, 3, 99]));
+    }
+
+    #[test]
+    fn test_scaffold_intersections_day17_example() {
+        let view = "\
+..#..........
+..#..........
+#######...###
+#.#...#...#.#
+#############
+..#...#...#..
+..#####...^..
+";
+        let mut program: Vec<i64> = view.bytes().flat_map(|b| [104, b.into()]).collect();
+        program.push(99);
+
+        let intersections = IntcodeVM::new(program).scaffold_intersections().unwrap();
+        assert_eq!(intersections, vec![(2, 2), (2, 4), (6, 4), (10, 4)]);
+        assert_eq!(intersections.iter().map(|(x, y)| x * y).sum::<usize>(), 76);
+
+        assert!(matches!(
+            IntcodeVM::new([104, 1000, 99]).scaffold_intersections(),
+            Err(VMError::InvalidAsciiOutput(1000))
+        ));
+    }
+
+    #[test]
+    fn test_input_into_immediate() {
+        // fails even before an input is provided
+        let mut vm = IntcodeVM::new([103, 0, 99]);
+        assert!(matches!(
+            vm.run(),
+            Err(VMError::InputTargetImmediate {
+                opcode: 103,
+                address: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn test_referenced_data_follows_both_branches() {
+        let vm = IntcodeVM::new([1005, 11, 6, 4, 12, 99, 4, 13, 99, 0, 0, 1, 0, 0]);
+        assert_eq!(
+            vm.referenced_data_addresses()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            [11, 12, 13]
+        );
+
+        // the condition being immediate, only one branch can be taken
+        let vm = IntcodeVM::new([1105, 0, 6, 4, 12, 99, 4, 13, 99, 0, 0, 1, 0, 0]);
+        assert_eq!(
+            vm.referenced_data_addresses()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            [12]
+        );
+    }
+
+    #[test]
+    fn test_view_matches_vm() {
+        let program: memory::Memory<i64> =
+            "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99"
+                .parse()
+                .unwrap();
+        let mut vm = IntcodeVM::from(program.clone());
+        let mut view = view::VmView::new(&program);
+
+        loop {
+            let result = vm.run().unwrap();
+            assert_eq!(view.run().unwrap(), result);
+            if result == VMResult::Halted {
+                break;
+            }
+        }
+
+        let memory = vm.into_memory();
+        assert_eq!(view.get(100), &memory[100]);
+        assert_eq!(view.get(101), &memory[101]);
+        assert_eq!(program[100], 0);
+    }
+
+    #[test]
+    fn test_view_shares_vm_execution() {
+        // outputs 3, 2, 1
+        let program = memory::Memory::from([4, 10, 1001, 10, -1, 10, 1005, 10, 0, 99, 3]);
+        let mut view = view::VmView::new(&program);
+        view.add_breakpoint(2);
+        assert_eq!(view.run().unwrap(), VMResult::Output(3));
+        assert_eq!(view.run().unwrap(), VMResult::BreakpointHit(2));
+        view.remove_breakpoint(2);
+        assert_eq!(view.run().unwrap(), VMResult::Output(2));
+
+        view.set_halt_behavior(vm::HaltBehavior::Pause);
+        assert_eq!(view.run().unwrap(), VMResult::Output(1));
+        assert_eq!(view.run().unwrap(), VMResult::Paused);
+        assert_eq!(view.overlay().len(), 1);
+        assert_eq!(program[10], 3);
+
+        let program = memory::Memory::<i64>::from([1101, 1, 1, 1_000_000, 99]);
+        let mut view = view::VmView::new(&program);
+        view.set_max_memory(1 << 10);
+        assert!(matches!(
+            view.run(),
+            Err(VMError::MemoryLimitExceeded {
+                address: 1_000_000,
+                limit: 1024
+            })
+        ));
+        assert!(view.overlay().is_empty());
+    }
+
+    #[test]
+    fn test_max_amplifier_signal_feedback_loop() {
+        let program: memory::Memory<i64> =
+            "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            network::max_amplifier_signal(&program, &[5, 6, 7, 8, 9]).unwrap(),
+            139629729
+        );
+
+        #[cfg(feature = "rayon")]
+        assert_eq!(
+            network::max_amplifier_signal_parallel(&program, &[5, 6, 7, 8, 9]).unwrap(),
+            139629729
+        );
+    }
+
+    #[test]
+    fn test_amplifier_deadlock() {
+        // wants 2 signals before producing anything
+        let program = memory::Memory::from([3, 11, 3, 11, 3, 11, 4, 11, 1105, 1, 2, 0]);
+        assert!(matches!(
+            network::max_amplifier_signal(&program, &[0, 1]),
+            Err(VMError::PipelineDeadlock { waiting }) if waiting == [0, 1]
+        ));
+    }
+
+    #[test]
+    fn test_amplifier_chain_keeps_every_output() {
+        // outputs its phase before reading its signal, then outputs the signal plus its phase
+        let program = memory::Memory::from([3, 100, 4, 100, 3, 101, 1, 100, 101, 102, 4, 102, 99]);
+        // the second amplifier receives 1 (the phase of the first one) as its signal,
+        // its own first output going back to the halted first amplifier
+        assert_eq!(
+            network::run_amplifier_chain(&program, &[1, 10], 0).unwrap(),
+            11
+        );
+        assert_eq!(
+            network::run_amplifier_chain(&program, &[1, 10, 100], 0).unwrap(),
+            110
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut vm = IntcodeVM::new([3, 13, 3, 14, 1, 13, 14, 13, 109, 5, 4, 13, 99, 0, 0]);
+        vm.extend_input([1, 2, 3]);
+        assert_eq!(vm.step().unwrap(), VMResult::Continue);
+
+        let json = serde_json::to_string(&vm).unwrap();
+        assert_eq!(
+            json,
+            r#"{"memory":[3,13,3,14,1,13,14,13,109,5,4,13,99,1,0],"instruction_pointer":2,"relative_base":0,"inputs":[2,3]}"#
+        );
+
+        let mut restored: IntcodeVM<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.run().unwrap(), VMResult::Output(3));
+        assert_eq!(restored.relative_base(), &5);
+        assert_eq!(restored.get_next_input(), &Some(3));
+
+        let result: VMResult<i32> = serde_json::from_str(r#"{"Output":42}"#).unwrap();
+        assert_eq!(result, VMResult::Output(42));
+    }
+
+    #[test]
+    fn test_decode_errors_report_address() {
+        assert!(matches!(
+            IntcodeVM::new([15]).run(),
+            Err(VMError::UnknownInstruction {
+                opcode: 15,
+                address: 0
+            })
+        ));
+
+        let mut vm = IntcodeVM::new([104, 1, 1101, 2, 3, 20, 42, 99]);
+        assert_eq!(vm.run().unwrap(), VMResult::Output(1));
+        assert!(matches!(
+            vm.run(),
+            Err(VMError::UnknownInstruction {
+                opcode: 42,
+                address: 6
+            })
+        ));
+
+        assert!(matches!(
+            IntcodeVM::new([104, 0, 30001, 0, 0, 0]).run_expect(&[], &[0]),
+            Err(VMError::InvalidArgMode {
+                arg_num: 3,
+                address: 2,
+                ..
+            })
+        ));
+
+        assert!(matches!(
+            IntcodeVM::new([104, 0, 11101, 1, 2, 3, 99]).run_expect(&[], &[0]),
+            Err(VMError::ArgModeCannotBeImmediate {
+                opcode: 11101,
+                arg_num: 3,
+                address: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_surrounding_whitespace() {
+        for program in ["1,0,0,3,99\n", " 1, 0, 0, 3, 99 ", "1,0,0,3,99,\n"] {
+            let memory: memory::Memory<i64> = program.parse().unwrap();
+            assert_eq!(memory.iter().copied().collect::<Vec<_>>(), [1, 0, 0, 3, 99]);
+        }
+
+        assert!("1,,0".parse::<memory::Memory<i64>>().is_err());
+    }
+
+    #[test]
+    fn test_parse_whitespace_separated() {
+        for program in [
+            "1 0 0 3 99",
+            "1\n0\n0\n3\n99\n",
+            "1\t0  0\r\n3 99",
+            "1, 0 0,3\n99,",
+            " 1 ,\n0 0 , 3,99 \n",
+        ] {
+            let memory: memory::Memory<i64> = program.parse().unwrap();
+            assert_eq!(memory.iter().copied().collect::<Vec<_>>(), [1, 0, 0, 3, 99]);
+            #[cfg(feature = "std")]
+            assert_eq!(
+                memory::Memory::<i64>::from_reader(program.as_bytes()).unwrap(),
+                memory
+            );
+        }
+
+        assert!("1 ,, 0".parse::<memory::Memory<i64>>().is_err());
+        assert!("1 0x 3".parse::<memory::Memory<i64>>().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_from_reader_across_buffer_boundaries() {
+        let program = (0..5000)
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let memory: memory::Memory<i64> =
+            memory::Memory::from_reader(std::io::BufReader::with_capacity(7, program.as_bytes()))
+                .unwrap();
+
+        assert_eq!(
+            memory.iter().copied().collect::<Vec<_>>(),
+            (0..5000).collect::<Vec<_>>()
+        );
+        assert!(memory::Memory::<i64>::from_reader("1,,2".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_fork_is_independent() {
+        let mut vm = IntcodeVM::new([3, 11, 1001, 11, 1, 11, 4, 11, 1105, 1, 0, 0]);
+        vm.push_input(10);
+        let fork = vm.fork();
+
+        assert_eq!(vm.run().unwrap(), VMResult::Output(11));
+        assert_eq!(vm.run().unwrap(), VMResult::WaitingForInput);
+
+        assert_eq!(fork.instruction_pointer(), 0);
+        assert_eq!(fork.get_next_input(), &Some(10));
+        assert_eq!(
+            fork.into_memory(),
+            memory::Memory::from([3, 11, 1001, 11, 1, 11, 4, 11, 1105, 1, 0])
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_run_with_channels_between_threads() {
+        use std::{sync::mpsc, thread};
+
+        // output their input + 1 (or times 2) until they read 0
+        let mut incrementer = IntcodeVM::new([
+            3, 15, 1006, 15, 14, 101, 1, 15, 15, 4, 15, 1105, 1, 0, 99, 0,
+        ]);
+        let mut doubler = IntcodeVM::new([
+            3, 15, 1006, 15, 14, 1002, 15, 2, 15, 4, 15, 1105, 1, 0, 99, 0,
+        ]);
+
+        let (input, incrementer_input) = mpsc::channel();
+        let (incrementer_output, doubler_input) = mpsc::channel();
+        let (doubler_output, output) = mpsc::channel();
+        let incrementer = thread::spawn(move || {
+            incrementer.run_with_channels(incrementer_input, incrementer_output)
+        });
+        let doubler =
+            thread::spawn(move || doubler.run_with_channels(doubler_input, doubler_output));
+
+        for value in [1, 2, 3, 0] {
+            input.send(value).unwrap();
+        }
+
+        assert_eq!(output.iter().collect::<Vec<_>>(), [4, 6, 8]);
+        assert!(incrementer.join().unwrap().is_ok());
+        // the incrementer halted without sending a 0 to the doubler
+        assert!(matches!(
+            doubler.join().unwrap(),
+            Err(VMError::InputDisconnected)
+        ));
+    }
+
+    #[test]
+    fn test_sparse_memory_matches_dense() {
+        // writes its input at address 1_000_000, then outputs it doubled
+        let program = [
+            3, 1_000_000, 1002, 1_000_000, 2, 1_000_000, 4, 1_000_000, 99,
+        ];
+        let dense = memory::Memory::from(program);
+        let sparse = dense.clone().into_sparse();
+        assert!(sparse.is_sparse() && !dense.is_sparse());
+        assert_eq!(sparse, dense);
+        assert!(sparse.iter().eq(dense.iter()));
+
+        let mut results = [dense, sparse].map(|memory| {
+            let mut vm = IntcodeVM::new(memory);
+            vm.push_input(21i64);
+            assert_eq!(vm.run().unwrap(), VMResult::Output(42));
+            assert_eq!(vm.run().unwrap(), VMResult::Halted);
+            vm.into_memory()
+        });
+
+        assert!(results[1].is_sparse());
+        assert_eq!(results[0].len(), results[1].len());
+        assert_eq!(results[0], results[1]);
+
+        results[1].set(10, 0);
+        assert_eq!(results[1].iter_mut().count(), 11);
+
+        // the stored zeros at the end still count in the length
+        let padded = memory::Memory::from(vec![1, 0, 0]);
+        let sparse = padded.clone().into_sparse();
+        assert_eq!(sparse.len(), 3);
+        assert_eq!(sparse.to_string(), padded.to_string());
+        assert!(sparse.iter().eq(padded.iter()));
+    }
+
+    #[test]
+    fn test_sparse_memory_far_address() {
+        let mut far = memory::Memory::sparse();
+        far.set(1 << 40, 1i64);
+        let mut other = far.clone();
+        assert_eq!(far, other);
+
+        other.set(1 << 40, 2);
+        other.set(3, 4);
+        assert_ne!(far, other);
+        assert_eq!(far.diff(&other), [(3, 0, 4), (1 << 40, 1, 2)]);
+        assert_eq!(far.len(), (1 << 40) + 1);
+    }
+
+    #[test]
+    fn test_memory_from_bytes() {
+        use error::FromBytesError;
+
+        let memory =
+            memory::Memory::<i64>::parse_bytes(b"1,9,10,3,2,3,11,0,99,30,40,50\n").unwrap();
+        assert_eq!(memory, "1,9,10,3,2,3,11,0,99,30,40,50".parse().unwrap());
+
+        let err = memory::Memory::<i64>::parse_bytes(b"1,0,\xc3\x28,99").unwrap_err();
+        assert!(matches!(err, FromBytesError::Utf8(_)));
+
+        let err = memory::Memory::<i64>::parse_bytes(b"1,0,-,99").unwrap_err();
+        assert!(matches!(err, FromBytesError::Parse(_)));
+        assert_eq!(
+            err.to_string(),
+            "Could not parse the program: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_breakpoint_on_input_instruction() {
+        // outputs its input times 3
+        let mut vm = IntcodeVM::new([3, 9, 1002, 9, 3, 9, 4, 9, 99, 0]);
+        vm.add_breakpoint(0);
+        vm.add_breakpoint(8);
+
+        assert_eq!(vm.run().unwrap(), VMResult::BreakpointHit(0));
+        // waiting for an input does not count as stepping off the breakpoint
+        assert_eq!(vm.run().unwrap(), VMResult::WaitingForInput);
+        vm.push_input(5);
+        assert_eq!(vm.run().unwrap(), VMResult::Output(15));
+
+        assert_eq!(vm.run().unwrap(), VMResult::BreakpointHit(8));
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+
+        vm.reset();
+        assert_eq!(vm.step().unwrap(), VMResult::BreakpointHit(0));
+        assert_eq!(vm.step().unwrap(), VMResult::WaitingForInput);
+    }
+
+    #[test]
+    fn test_watchpoint_on_input_and_comparison() {
+        // stores its input at 9, then whether it equals 8 at 9
+        let mut vm = IntcodeVM::new([3, 9, 1008, 9, 8, 9, 4, 9, 99, 0]);
+        vm.add_watchpoint(9);
+        vm.push_input(8);
+
+        assert_eq!(
+            vm.step().unwrap(),
+            VMResult::WatchpointHit {
+                address: 9,
+                old: 0,
+                new: 8
+            }
+        );
+        assert_eq!(
+            vm.run().unwrap(),
+            VMResult::WatchpointHit {
+                address: 9,
+                old: 8,
+                new: 1
+            }
+        );
+        assert_eq!(vm.run().unwrap(), VMResult::Output(1));
+
+        vm.reset();
+        vm.remove_watchpoint(9);
+        vm.push_input(7);
+        assert_eq!(vm.run().unwrap(), VMResult::Output(0));
+    }
+
+    #[test]
+    fn test_day2_noun_verb_patches() {
+        let program: memory::Memory<i64> = "1,9,10,3,2,3,11,0,99,30,40,50".parse().unwrap();
+
+        let mut vm = IntcodeVM::new(program.clone()).with_patches([(1, 9), (2, 10)]);
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+        assert_eq!(vm.into_memory().get(0), &3500);
+
+        let mut vm = IntcodeVM::new(program).with_patch(1, 10).with_patch(2, 11);
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+        assert_eq!(vm.memory_len(), 12);
+        vm.reset();
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+        assert_eq!(vm.into_memory().get(0), &4500);
+    }
+
+    #[test]
+    fn test_search_noun_verb() {
+        // multiplies the values at the noun and verb addresses, into address 0
+        let program = memory::Memory::from([2, 0, 0, 0, 99, 7, 11]);
+        assert_eq!(
+            search::search_noun_verb(&program, 77, 0..=6).unwrap(),
+            Some((5, 6))
+        );
+        // the first pair in the search order
+        assert_eq!(
+            search::search_noun_verb(&program, 4, 0..=6).unwrap(),
+            Some((0, 0))
+        );
+        assert_eq!(search::search_noun_verb(&program, 78, 0..=6).unwrap(), None);
+
+        let asks_input = memory::Memory::from([3, 0, 0, 99]);
+        assert!(matches!(
+            search::search_noun_verb(&asks_input, 0, 0..=1),
+            Err(VMError::UnexpectedInputRequest)
+        ));
+
+        // the range ends at the largest `u8`
+        let program = memory::Memory::<u8>::from([1, 0, 0, 0, 99]);
+        assert_eq!(
+            search::search_noun_verb(&program, 1, 250..=255).unwrap(),
+            None
+        );
+        assert_eq!(
+            search::search_noun_verb(&program, 198, 4..=255).unwrap(),
+            Some((4, 4))
+        );
+    }
+
+    #[test]
+    fn test_collect_output_chunks() {
+        // outputs 0, 1, 2, ... up to its input (excluded)
+        let program = [
+            3, 100, // reads the input at 100
+            8, 100, 101, 102, 1005, 102, 18, // halts if the counter at 101 reached it
+            4, 101, 1001, 101, 1, 101, // outputs the counter then increments it
+            1105, 1, 2, 99,
+        ];
+
+        let mut vm = IntcodeVM::new(program);
+        vm.push_input(6);
+        assert_eq!(
+            vm.collect_output_chunks::<2>().unwrap(),
+            [[0, 1], [2, 3], [4, 5]]
+        );
+
+        let mut vm = IntcodeVM::new(program);
+        vm.push_input(6);
+        assert_eq!(
+            vm.collect_output_chunks::<3>().unwrap(),
+            [[0, 1, 2], [3, 4, 5]]
+        );
+
+        let mut vm = IntcodeVM::new(program);
+        vm.push_input(5);
+        assert!(matches!(
+            vm.collect_output_chunks::<3>(),
+            Err(VMError::IncompleteOutputChunk {
+                size: 3,
+                leftover: 2
+            })
+        ));
+
+        let mut vm = IntcodeVM::new(program);
+        vm.push_input(0);
+        assert!(vm.collect_output_chunks::<2>().unwrap().is_empty());
+    }
+
+    // reads an input, then outputs it times 3
+    const TRIPLE: [i64; 10] = [3, 9, 1002, 9, 3, 9, 4, 9, 99, 0];
+
+    #[test]
+    fn test_run_to_halt_resumes_past_breakpoints() {
+        let mut vm = IntcodeVM::new(TRIPLE);
+        vm.add_breakpoint(2);
+        vm.add_breakpoint(6);
+
+        let mut outputs = Vec::new();
+        vm.run_to_halt(|| 5, |out| outputs.push(out)).unwrap();
+        assert_eq!(outputs, [15]);
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    }
+
+    #[test]
+    fn test_run_expect_resumes_past_breakpoints() {
+        let mut vm = IntcodeVM::new(TRIPLE);
+        vm.add_breakpoint(2);
+        assert!(vm.run_expect(&[5], &[15]).is_ok());
+
+        let mut vm = IntcodeVM::new(TRIPLE);
+        vm.add_breakpoint(6);
+        assert!(matches!(
+            vm.run_expect(&[5], &[15, 0]),
+            Err(VMError::OutputMismatch {
+                index: 1,
+                actual: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_run_reactive_resumes_past_breakpoints() {
+        // outputs its input + 1, forever
+        let mut vm = IntcodeVM::new([3, 9, 1001, 9, 1, 9, 4, 9, 1105, 1, 0]);
+        vm.add_breakpoint(2);
+        let outputs = vm
+            .run_reactive(|outputs| match outputs.last() {
+                Some(&last) if last >= 3 => None,
+                Some(&last) => Some(last),
+                None => Some(0),
+            })
+            .unwrap();
+        assert_eq!(outputs, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_run_to_input_checkpoint_resumes_past_breakpoints() {
+        let mut vm = IntcodeVM::new([104, 7, 3, 11, 1002, 11, 2, 11, 4, 11, 99, 0]);
+        vm.add_breakpoint(2);
+        let checkpoint = vm.run_to_input_checkpoint().unwrap().unwrap();
+        assert_eq!(checkpoint.outputs(), &[7]);
+        assert_eq!(
+            checkpoint.resume_with(4).run().unwrap(),
+            VMResult::Output(8)
+        );
+    }
+
+    #[test]
+    fn test_run_diagnostic_resumes_past_breakpoints() {
+        let mut vm = IntcodeVM::new([104, 0, 104, 0, 3, 9, 4, 9, 99, 0]);
+        vm.add_breakpoint(2);
+        vm.add_breakpoint(6);
+        assert_eq!(vm.run_diagnostic(42).unwrap(), 42);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_run_with_channels_resumes_past_breakpoints() {
+        use std::sync::mpsc;
+
+        let mut vm = IntcodeVM::new(TRIPLE);
+        vm.add_breakpoint(2);
+        let (input, vm_input) = mpsc::channel();
+        let (vm_output, output) = mpsc::channel();
+        input.send(5).unwrap();
+
+        vm.run_with_channels(vm_input, vm_output).unwrap();
+        assert_eq!(output.iter().collect::<Vec<_>>(), [15]);
+    }
+
+    #[test]
+    fn test_scaffold_intersections_resumes_past_breakpoints() {
+        let mut program: Vec<i64> = ".#.\n###\n.#.\n"
+            .bytes()
+            .flat_map(|b| [104, b.into()])
+            .collect();
+        program.push(99);
+
+        let mut vm = IntcodeVM::new(program);
+        vm.add_breakpoint(8);
+        assert_eq!(vm.scaffold_intersections().unwrap(), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn test_collect_ascii_resumes_past_breakpoints() {
+        let mut vm = IntcodeVM::new([104, 72, 104, 105, 104, 1000, 99]);
+        vm.add_breakpoint(2);
+        vm.add_breakpoint(4);
+        let (text, others) = ascii::collect_ascii(&mut vm).unwrap();
+        assert_eq!(text, "Hi");
+        assert_eq!(others, [1000]);
+    }
+
+    #[test]
+    fn test_restore_hits_breakpoint_again() {
+        let mut vm = IntcodeVM::new([104, 1, 104, 2, 99]);
+        vm.add_breakpoint(2);
+        assert_eq!(vm.run().unwrap(), VMResult::Output(1));
+        assert_eq!(vm.run().unwrap(), VMResult::BreakpointHit(2));
+
+        let snapshot = vm.snapshot();
+        assert_eq!(vm.run().unwrap(), VMResult::Output(2));
+        vm.restore(snapshot);
+        assert_eq!(vm.run().unwrap(), VMResult::BreakpointHit(2));
+        assert_eq!(vm.run().unwrap(), VMResult::Output(2));
+    }
+
+    #[test]
+    fn test_helpers_resume_past_watchpoints() {
+        let mut vm = IntcodeVM::new(TRIPLE);
+        vm.add_watchpoint(9);
+        let mut outputs = Vec::new();
+        vm.run_to_halt(|| 5, |out| outputs.push(out)).unwrap();
+        assert_eq!(outputs, [15]);
+
+        let mut vm = IntcodeVM::new(TRIPLE);
+        vm.add_watchpoint(9);
+        assert!(vm.run_expect(&[5], &[15]).is_ok());
+
+        let mut vm = IntcodeVM::new([3, 9, 1001, 9, 1, 9, 4, 9, 1105, 1, 0]);
+        vm.add_watchpoint(9);
+        let outputs = vm
+            .run_reactive(|outputs| match outputs.last() {
+                Some(&last) if last >= 3 => None,
+                Some(&last) => Some(last),
+                None => Some(0),
+            })
+            .unwrap();
+        assert_eq!(outputs, [1, 2, 3]);
+
+        // writes to 11 before asking for an input
+        let mut vm = IntcodeVM::new([1101, 3, 4, 11, 4, 11, 3, 11, 4, 11, 99, 0]);
+        vm.add_watchpoint(11);
+        let checkpoint = vm.run_to_input_checkpoint().unwrap().unwrap();
+        assert_eq!(checkpoint.outputs(), &[7]);
+
+        let mut vm = IntcodeVM::new([104, 0, 104, 0, 3, 9, 4, 9, 99, 0]);
+        vm.add_watchpoint(9);
+        assert_eq!(vm.run_diagnostic(42).unwrap(), 42);
+
+        let mut vm = IntcodeVM::new([1101, 72, 0, 7, 4, 7, 99, 0]);
+        vm.add_watchpoint(7);
+        assert_eq!(
+            ascii::collect_ascii(&mut vm).unwrap(),
+            ("H".to_owned(), vec![])
+        );
+    }
+
+    #[test]
+    fn test_strict_memory() {
+        // outputs the cell before the relative base, one cell too far
+        let program = [109, 7, 204, 0, 99, 10, 20];
+        let mut vm = IntcodeVM::new(program);
+        assert_eq!(vm.run().unwrap(), VMResult::Output(0));
+
+        let mut vm = IntcodeVM::new(program);
+        vm.set_strict_memory(true);
+        assert!(matches!(vm.run(), Err(VMError::AddressOutOfBounds(7))));
+
+        // the cells written past the end can be read back
+        let mut vm = IntcodeVM::new([1101, 2, 3, 7, 4, 7, 99]);
+        vm.set_strict_memory(true);
+        assert_eq!(vm.run().unwrap(), VMResult::Output(5));
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+
+        // an instruction whose arguments are cut off by the end of the memory
+        let mut vm = IntcodeVM::new([1101, 2]);
+        vm.set_strict_memory(true);
+        assert!(matches!(vm.run(), Err(VMError::AddressOutOfBounds(2))));
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let mut vm = IntcodeVM::<i32>::new([1, 0, 0, 0, 1101, i32::MAX, 1, 0, 99]);
+        vm.set_checked_arithmetic(true);
+        assert!(matches!(
+            vm.run(),
+            Err(VMError::ArithmeticOverflow { opcode: 1, ip: 4 })
+        ));
+
+        let mut vm = IntcodeVM::<i8>::new([2, 9, 10, 0, 2, 9, 11, 0, 99, -64, 2, 3]);
+        vm.set_checked_arithmetic(true);
+        assert!(matches!(
+            vm.run(),
+            Err(VMError::ArithmeticOverflow { opcode: 2, ip: 4 })
+        ));
+    }
+
+    #[test]
+    fn test_output_callback_replaces_buffering() {
+        use std::sync::{Arc, Mutex};
+
+        let outputs = Arc::new(Mutex::new(Vec::new()));
+        let mut vm = IntcodeVM::new([104, 1, 3, 9, 4, 9, 104, 3, 99, 0]);
+        vm.set_buffer_output(true);
+        assert_eq!(vm.run().unwrap(), VMResult::WaitingForInput);
+        assert_eq!(vm.peek_output(), &[1]);
+
+        let sink = Arc::clone(&outputs);
+        vm.on_output(move |out| sink.lock().unwrap().push(out));
+        vm.push_input(2);
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+        assert_eq!(*outputs.lock().unwrap(), [2, 3]);
+        assert_eq!(vm.drain_output(), [1]);
+
+        // the callback is not cloned
+        let mut copy = vm.clone();
+        copy.reset();
+        copy.set_buffer_output(false);
+        assert_eq!(copy.run().unwrap(), VMResult::Output(1));
+    }
+
+    #[test]
+    fn test_memory_from_str_radix() {
+        let memory = memory::Memory::<i64>::from_str_radix("3ea,-ff, 63 ,", 16).unwrap();
+        assert_eq!(memory.as_slice(), Some(&[1002, -255, 99][..]));
+
+        let memory = memory::Memory::<i64>::from_str_radix("1,1100011", 2).unwrap();
+        assert_eq!(memory.as_slice(), Some(&[1, 99][..]));
+
+        let err = memory::Memory::<i64>::from_str_radix("1,0,2,3", 2).unwrap_err();
+        assert!(matches!(
+            err,
+            error::MemoryParseError::Invalid { token, .. } if token == "2"
+        ));
+    }
+
+    #[test]
+    fn test_memory_from_vec_keeps_allocation() {
+        // a large program: outputs 0, then the cells of a big data block it never reads
+        let mut program = vec![0i64; 1 << 20];
+        program[..3].copy_from_slice(&[104, 0, 99]);
+        let address = program.as_ptr();
+
+        let memory = memory::Memory::from_vec(program);
+        assert_eq!(memory.as_slice().unwrap().as_ptr(), address);
+
+        let mut vm = IntcodeVM::new(memory.clone());
+        assert_eq!(vm.run().unwrap(), VMResult::Output(0));
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+
+        let copy = memory::Memory::from_slice(memory.as_slice().unwrap());
+        assert_eq!(copy, memory);
+        assert_ne!(copy.as_slice().unwrap().as_ptr(), address);
+    }
+
+    #[test]
+    fn test_wrapping_vm() {
+        use num::Integer;
+        use wrapping::Wrapping;
+
+        // outputs i64::MAX * 2, then i64::MIN + (-1) compared with i64::MAX
+        let program = "1102,9223372036854775807,2,0,4,0,1101,-9223372036854775808,-1,0,1008,0,9223372036854775807,0,4,0,99";
+        let mut vm: WrappingVM = program.parse().unwrap();
+        assert_eq!(vm.run().unwrap(), VMResult::Output(Wrapping(-2)));
+        assert_eq!(vm.run().unwrap(), VMResult::Output(Wrapping(1)));
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+
+        let min = Wrapping(i64::MIN);
+        assert_eq!(min.div_rem(&Wrapping(-1)), (min, Wrapping(0)));
+        assert_eq!(min.div_floor(&Wrapping(-1)), min);
+        assert_eq!(Wrapping(-7i64).mod_floor(&Wrapping(2)), Wrapping(1));
+
+        let mut vm = WrappingVM::new([3, 0, 4, 0, 99].map(Wrapping));
+        ascii::feed_line(&mut vm, "");
+        assert_eq!(vm.run().unwrap(), VMResult::Output(Wrapping(10)));
+    }
+
+    #[test]
+    fn test_code_limit() {
+        // jumps to the address given as input, 7 being the output instruction after the halt
+        let program = [3, 11, 105, 1, 11, 99, 0, 104, 42, 99, 0, 0];
+        let run_with = |target, limit| {
+            let mut vm = IntcodeVM::new(program);
+            vm.set_code_limit(limit);
+            vm.push_input(target);
+            vm.run()
+        };
+
+        assert_eq!(run_with(7, 10).unwrap(), VMResult::Output(42));
+        assert!(matches!(
+            run_with(7, 7),
+            Err(VMError::InstructionPointerOutOfRange(7))
+        ));
+        assert!(matches!(
+            run_with(5000, 12),
+            Err(VMError::InstructionPointerOutOfRange(5000))
+        ));
+    }
+
+    #[test]
+    fn test_memory_resize_and_shrink() {
+        for mut memory in [
+            memory::Memory::from([1, 0, 0, 3, 99, 0, 0]),
+            memory::Memory::from([1, 0, 0, 3, 99, 0, 0]).into_sparse(),
+        ] {
+            memory.set(1 << 20, 7);
+            memory.resize_to(10);
+            assert_eq!(memory.len(), 10);
+            assert_eq!(memory.get(1 << 20), &0);
+
+            memory.shrink_to_fit();
+            assert_eq!(memory.len(), 5);
+            assert!(memory.memory_starts_with(&[1, 0, 0, 3, 99]));
+
+            memory.resize_to(2);
+            assert_eq!(memory.len(), 2);
+            assert_eq!(memory.get(3), &0);
+            memory.resize_to(4);
+            assert!(memory.memory_starts_with(&[1, 0, 0, 0]));
+        }
+
+        let mut memory = memory::Memory::<i64>::from_vec(Vec::with_capacity(1000));
+        memory.set(2, 0);
+        memory.shrink_to_fit();
+        assert!(memory.is_empty());
+        assert_eq!(memory.as_slice().map(<[_]>::len), Some(0));
+    }
+
+    #[test]
+    fn test_builder() {
+        let mut vm = IntcodeVM::<i64>::builder().build();
+        assert_eq!(vm.memory_len(), 0);
+        assert!(matches!(
+            vm.run(),
+            Err(VMError::UnknownInstruction {
+                opcode: 0,
+                address: 0
+            })
+        ));
+
+        // outputs its input + 1 forever
+        let mut vm = IntcodeVM::builder()
+            .memory([3, 11, 1001, 11, 1, 11, 4, 11, 1105, 1, 0])
+            .inputs([1, 2])
+            .input_on_empty(vm::EmptyInputBehavior::Yield(10))
+            .instruction_limit(10)
+            .input_logging()
+            .build();
+        let outputs: Vec<_> = vm.outputs().map_while(Result::ok).collect();
+        assert_eq!(outputs, [2, 3]);
+        assert!(matches!(
+            vm.step(),
+            Err(VMError::InstructionLimitExceeded { executed: 10 })
+        ));
+        assert_eq!(vm.instructions_executed(), 10);
+        assert_eq!(vm.input_log().len(), 3);
+    }
+
+    #[test]
+    fn test_push_ascii_line() {
+        // outputs its first 6 inputs
+        let mut program = [3, 0, 4, 0].repeat(6);
+        program.push(99);
+
+        for line in ["north\n", "north"] {
+            let mut vm = IntcodeVM::<i64>::new(program.clone());
+            vm.push_ascii_line(line).unwrap();
+            let outputs: Vec<_> = vm.outputs().collect::<Result<_, _>>().unwrap();
+            assert_eq!(outputs, [110, 111, 114, 116, 104, 10]);
+        }
+
+        let mut vm = IntcodeVM::<i64>::new(program);
+        assert!(matches!(
+            vm.push_ascii_line("nörth"),
+            Err(VMError::NonAsciiInput('ö'))
+        ));
+        assert_eq!(vm.get_next_input(), &None);
+    }
+
+    #[test]
+    fn test_step_back_restores_input_and_memory_len() {
+        let mut vm = IntcodeVM::new([109, 5, 3, 100, 99]);
+        vm.enable_history(10);
+        vm.extend_input([7, 8]);
+        assert!(!vm.step_back());
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+        assert_eq!(vm.memory_len(), 101);
+
+        // the halt is not kept
+        assert!(vm.step_back());
+        assert_eq!(vm.instruction_pointer(), 2);
+        assert_eq!(vm.memory_len(), 5);
+        assert_eq!(vm.get_next_input(), &Some(7));
+
+        assert!(vm.step_back());
+        assert_eq!(vm.instruction_pointer(), 0);
+        assert_eq!(vm.relative_base(), &0);
+        assert!(!vm.step_back());
+
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+        assert_eq!(vm.clone().into_memory().get(100), &7);
+        assert_eq!(vm.get_next_input(), &Some(8));
+
+        vm.reset();
+        assert!(!vm.step_back());
+    }
+
+    #[test]
+    fn test_run_to_halt_and_read() {
+        // stores its input times 3 at 9, outputting it on the way
+        let mut vm = IntcodeVM::new([3, 9, 1002, 9, 3, 9, 4, 9, 99, 0]);
+        vm.add_breakpoint(2);
+        vm.push_input(14);
+        assert_eq!(vm.run_to_halt_and_read(9).unwrap(), 42);
+        assert_eq!(vm.run_to_halt_and_read(1000).unwrap(), 0);
+
+        vm.reset();
+        assert!(matches!(
+            vm.run_to_halt_and_read(9),
+            Err(VMError::UnexpectedInputRequest)
+        ));
+    }
+
+    #[test]
+    fn test_big_integer_program() {
+        use num::BigInt;
+
+        let big = |s: &str| s.parse::<BigInt>().unwrap();
+
+        // adds 2^100 to its input, copies the result at 2^20 then outputs it through the relative base
+        let program = "3,100,1001,100,1267650600228229401496703205376,100,\
+                       1001,100,0,1048576,109,1048576,204,0,99";
+        let mut vm: IntcodeVM<BigInt> = program.parse().unwrap();
+        vm.push_input(big("-1"));
+        assert_eq!(
+            vm.run().unwrap(),
+            VMResult::Output(big("1267650600228229401496703205375"))
+        );
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+
+        // a valid integer far beyond usize::MAX
+        let mut vm: IntcodeVM<BigInt> = "4,1267650600228229401496703205376,99".parse().unwrap();
+        assert!(
+            matches!(vm.run(), Err(VMError::AddressTooLarge(address)) if address == big("1267650600228229401496703205376"))
+        );
+
+        let mut vm: IntcodeVM<BigInt> = "1105,1,-3,99".parse().unwrap();
+        assert!(matches!(vm.run(), Err(VMError::CannotCastToUsize(target)) if target == big("-3")));
+    }
+
+    #[test]
+    fn test_run_collecting_until_halt() {
+        // outputs 3, 2, 1
+        let mut vm = IntcodeVM::new([4, 10, 1001, 10, -1, 10, 1005, 10, 0, 99, 3]);
+        vm.add_breakpoint(2);
+        assert_eq!(vm.run_collecting().unwrap(), (vec![3, 2, 1], true));
+        assert_eq!(vm.run_collecting().unwrap(), (vec![], true));
+    }
+
+    #[test]
+    fn test_run_collecting_until_input() {
+        // outputs the running sum of its inputs
+        let mut vm = IntcodeVM::new([3, 11, 1, 11, 12, 12, 4, 12, 1105, 1, 0, 0, 0]);
+        vm.extend_input([1, 2, 3]);
+        assert_eq!(vm.run_collecting().unwrap(), (vec![1, 3, 6], false));
+
+        vm.push_input(4);
+        assert_eq!(vm.run_collecting().unwrap(), (vec![10], false));
+    }
+
+    #[test]
+    fn test_output_mapped_to_memory() {
+        // outputs 1 then its input
+        let program = [104, 1, 3, 100, 4, 100, 99];
+        let mut vm = IntcodeVM::new(program);
+        vm.map_output_to_memory(50);
+        vm.set_buffer_output(true);
+        assert_eq!(vm.run().unwrap(), VMResult::WaitingForInput);
+        vm.push_input(7);
+        assert_eq!(vm.run().unwrap(), VMResult::Halted);
+        assert!(vm.drain_output().is_empty());
+        assert_eq!(vm.clone().into_memory().get(50), &7);
+
+        vm.reset();
+        vm.unmap_output();
+        vm.set_buffer_output(false);
+        assert_eq!(vm.run().unwrap(), VMResult::Output(1));
+
+        let mut vm = IntcodeVM::new(program);
+        vm.map_output_to_memory(10);
+        vm.set_max_memory(10);
+        assert!(matches!(
+            vm.run(),
+            Err(VMError::MemoryLimitExceeded {
+                address: 10,
+                limit: 10
+            })
+        ));
+    }
+
+    #[test]
+    fn test_memory_hash_ignores_trailing_zeros() {
+        use std::collections::HashSet;
+
+        let mut sparse = memory::Memory::sparse();
+        sparse.set(1000, 0);
+        sparse.set(2, 5);
+        let mut dense = memory::Memory::from([0, 0, 5]);
+        dense.set(50, 0);
+
+        let mut seen = HashSet::new();
+        assert!(seen.insert(dense.clone()));
+        assert!(!seen.insert(sparse));
+        assert!(!seen.insert(memory::Memory::from([0, 0, 5])));
+        assert!(seen.insert(memory::Memory::from([0, 5])));
+        assert!(seen.insert(memory::Memory::from([0, 0, 5, 1])));
+        assert_eq!(seen.len(), 3);
+    }
+
+    #[test]
+    fn test_vm_equality_covers_inputs_and_relative_base() {
+        let vm = IntcodeVM::new([109, 1, 3, 0, 99]);
+        let mut other = vm.clone();
+        other.enable_trace();
+        other.add_breakpoint(4);
+        assert_eq!(vm, other);
+
+        other.push_input(1);
+        assert_ne!(vm, other);
+
+        let mut vm = vm.fork();
+        vm.push_input(1);
+        assert_eq!(vm, other);
+
+        // same memory and instruction pointer, different relative base
+        let mut stepped = vm.clone();
+        assert_eq!(stepped.step().unwrap(), VMResult::Continue);
+        stepped.set_instruction_pointer(0);
+        assert_ne!(stepped, vm);
+        stepped.set_relative_base(0);
+        assert_eq!(stepped, vm);
+    }
+
+    #[test]
+    fn test_decode_at_matches_execution() {
+        let vm = IntcodeVM::new([109, 19, 21101, 2, 3, -1, 3, 0, 1006, 0, 99, 99]);
+
+        let decoded: Vec<_> = [0, 2, 6, 8, 10]
+            .into_iter()
+            .map(|address| vm.decode_at(address).unwrap())
+            .collect();
+        assert_eq!(
+            decoded
+                .iter()
+                .map(|instr| (instr.address, instr.opcode, instr.width()))
+                .collect::<Vec<_>>(),
+            [(0, 9, 2), (2, 1, 4), (6, 3, 2), (8, 6, 3), (10, 99, 1)]
+        );
+        assert_eq!(decoded[1].operands, [(1, 2), (1, 3), (2, -1)]);
+        assert!(decoded[4].operands.is_empty());
+
+        // reading past the end decodes the missing operands as 0, like executing does
+        let vm = IntcodeVM::new([1, 0]);
+        assert_eq!(vm.decode_at(0).unwrap().operands, [(0, 0), (0, 0), (0, 0)]);
+        assert!(matches!(
+            IntcodeVM::new([3]).with_patch(0, 103).decode_at(0),
+            Err(VMError::InputTargetImmediate {
+                opcode: 103,
+                address: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn test_input_iter_precedence() {
+        // outputs its input, forever
+        let program = [3, 7, 4, 7, 1105, 1, 0, 0];
+        let mut vm = IntcodeVM::new(program);
+        vm.set_input_iter((10..).step_by(10));
+        vm.set_input_on_empty(vm::EmptyInputBehavior::Yield(-1));
+        vm.push_input(1);
+        let outputs: Vec<_> = vm.outputs().take(4).collect::<Result<_, _>>().unwrap();
+        assert_eq!(outputs, [1, 10, 20, 30]);
+
+        vm.set_next_input(2);
+        assert_eq!(vm.run().unwrap(), VMResult::Output(2));
+        assert_eq!(vm.run().unwrap(), VMResult::Output(40));
+
+        // clones do not get the iterator
+        let mut clone = vm.clone();
+        clone.set_input_on_empty(vm::EmptyInputBehavior::WaitForInput);
+        assert_eq!(clone.run().unwrap(), VMResult::WaitingForInput);
+
+        // the NAT's constant -1, once the iterator is exhausted
+        let mut vm = IntcodeVM::new(program);
+        vm.set_input_iter([5].into_iter());
+        vm.set_input_on_empty(vm::EmptyInputBehavior::Yield(-1));
+        vm.enable_history(4);
+        assert_eq!(vm.run().unwrap(), VMResult::Output(5));
+        assert!(vm.step_back());
+        assert!(vm.step_back());
+        assert_eq!(vm.get_next_input(), &Some(5));
+        assert_eq!(vm.run().unwrap(), VMResult::Output(5));
+        assert_eq!(vm.run().unwrap(), VMResult::Output(-1));
+    }
+
+    #[test]
+    fn test_parse_verbose_reports_every_field() {
+        let errors = memory::Memory::<i8>::parse_verbose("1,,300, -5 ,a,99,\n").unwrap_err();
+        assert_eq!(
+            errors,
+            [
+                (1, String::new()),
+                (2, "300".to_owned()),
+                (4, "a".to_owned())
+            ]
+        );
+
+        let memory = memory::Memory::<i8>::parse_verbose(" 1, 0, 0, 3, 99,\n").unwrap();
+        assert_eq!(memory, "1,0,0,3,99".parse().unwrap());
+    }
+
+    #[test]
+    fn test_fuel_round_robin() {
+        // adds 1 to its counter then outputs it, forever
+        let program = [1001, 9, 1, 9, 4, 9, 1105, 1, 0];
+        let mut vms: Vec<_> = (0..3)
+            .map(|_| IntcodeVM::builder().memory(program).fuel(0).build())
+            .collect();
+        vms[1].add_breakpoint(4);
+
+        let mut outputs = vec![Vec::new(); 3];
+        for _ in 0..4 {
+            for (vm, outputs) in vms.iter_mut().zip(&mut outputs) {
+                vm.add_fuel(3);
+                loop {
+                    match vm.run().unwrap() {
+                        VMResult::Output(out) => outputs.push(out),
+                        VMResult::OutOfFuel => break,
+                        VMResult::BreakpointHit(4) => (),
+                        result => panic!("unexpected {result:?}"),
+                    }
+                }
+            }
+        }
+
+        assert!(
+            outputs.iter().all(|outputs| outputs == &[1, 2, 3, 4]),
+            "{outputs:?}"
+        );
+        assert!(vms.iter().all(|vm| vm.instructions_executed() == 12));
+
+        assert!(matches!(
+            vms[0].run_collecting(),
+            Err(error::VMError::OutOfFuel)
+        ));
+        vms[0].add_fuel(2);
+        assert!(matches!(vms[0].next_output(), Ok(Some(5))));
+    }
+
+    #[test]
+    fn test_run_arcade() {
+        // draws a wall, a block twice, erases the other block and displays a score
+        let program = memory::Memory::from([
+            104, 0, 104, 0, 104, 1, 104, 1, 104, 0, 104, 2, 104, 2, 104, 0, 104, 2, 104, 1, 104, 0,
+            104, 2, 104, 2, 104, 0, 104, 0, 104, -1, 104, 0, 104, 7, 99,
+        ]);
+        let arcade = games::run_arcade(program).unwrap();
+        assert_eq!(arcade.count(games::Tile::Block), 1);
+        assert_eq!(arcade.count(games::Tile::Wall), 1);
+        assert_eq!(arcade.screen().len(), 3);
+        assert_eq!(arcade.score(), Some(7));
+
+        let program = memory::Memory::from([104, 0, 104, 0, 104, 5, 99]);
+        assert!(matches!(
+            games::run_arcade(program),
+            Err(error::VMError::InvalidArcadeOutput(5))
+        ));
+        let program = memory::Memory::from([104, 0, 104, 0, 99]);
+        assert!(matches!(
+            games::run_arcade(program),
+            Err(error::VMError::IncompleteOutputChunk { .. })
+        ));
+    }
+
+    #[test]
+    fn test_run_painting_robot() {
+        // paints each panel with its own color, turning left then right
+        let program = memory::Memory::from([3, 100, 4, 100, 104, 0, 3, 100, 4, 100, 104, 1, 99]);
+        let panels = games::run_painting_robot(program.clone(), true).unwrap();
+        assert_eq!(
+            panels.into_iter().collect::<Vec<_>>(),
+            [((-1, 0), false), ((0, 0), true)]
+        );
+        let panels = games::run_painting_robot(program, false).unwrap();
+        assert!(panels.values().all(|&white| !white));
+
+        for (program, expected) in [
+            (
+                vec![104, 2, 104, 0, 99],
+                "Expected 0 or 1 as output (a color or a turn), got 2",
+            ),
+            (
+                vec![104, 1, 3, 100, 99],
+                "The VM requested an input but none was provided",
+            ),
+            (
+                vec![104, 1, 99],
+                "The VM halted with 1 output(s) left over, not enough for a chunk of 2",
+            ),
+        ] {
+            let err = games::run_painting_robot(memory::Memory::from(program), false).unwrap_err();
+            assert_eq!(err.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_memory_fill() {
+        let mut memory = memory::Memory::with_fill(vec![1, 7, 7], 7);
+        memory.set(5, 2);
+        assert_eq!(
+            memory.iter().copied().collect::<Vec<_>>(),
+            [1, 7, 7, 7, 7, 2]
+        );
+        assert_eq!(
+            memory,
+            memory::Memory::with_fill(vec![1, 7, 7, 7, 7, 2, 7], 7)
+        );
+        assert_ne!(memory, memory::Memory::from([1, 7, 7, 7, 7, 2]));
+
+        let sparse = memory.clone().into_sparse();
+        assert_eq!(sparse.try_peek(1), None);
+        assert_eq!(sparse.get(1), &7);
+        assert_eq!(sparse, memory);
+
+        memory.set(9, 7);
+        memory.shrink_to_fit();
+        assert_eq!(memory.len(), 6);
+    }
+
+    #[cfg(feature = "log")]
+    mod logging {
+        use std::cell::RefCell;
+
+        use super::*;
+
+        std::thread_local! {
+            static MESSAGES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+        }
+
+        /// Records the messages of the current thread, the tests running in parallel
+        struct ThreadLogger;
+
+        impl log::Log for ThreadLogger {
+            fn enabled(&self, _: &log::Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, record: &log::Record) {
+                MESSAGES.with_borrow_mut(|messages| {
+                    messages.push(format!("{} {}", record.level(), record.args()))
+                });
+            }
+
+            fn flush(&self) {}
+        }
+
+        #[test]
+        fn test_log_messages() {
+            let _ = log::set_logger(&ThreadLogger);
+            log::set_max_level(log::LevelFilter::Trace);
+
+            let mut vm = IntcodeVM::new([3, 9, 1001, 9, 2, 10, 4, 10, 99, 0, 0]);
+            assert_eq!(vm.run().unwrap(), VMResult::WaitingForInput);
+            vm.push_input(5);
+            assert_eq!(vm.run_collecting().unwrap(), (vec![7], true));
+
+            assert_eq!(
+                MESSAGES.take(),
+                [
+                    "TRACE ip=0 opcode=3 operands=[@9]",
+                    "DEBUG ip=0 waiting for input",
+                    "TRACE ip=0 opcode=3 operands=[@9]",
+                    "DEBUG ip=0 input=5",
+                    "TRACE ip=2 opcode=1 operands=[5, 2, @10]",
+                    "TRACE ip=6 opcode=4 operands=[7]",
+                    "DEBUG ip=6 output=7",
+                    "TRACE ip=8 opcode=99 operands=[]",
+                    "DEBUG ip=8 halt",
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_radix_round_trip() {
+        // deterministic pseudo-random programs, covering the extreme values
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1);
+            let value = (state >> 11) as i64 >> (state % 53);
+            if state & 1 == 0 {
+                value
+            } else {
+                -value
+            }
+        };
+        for len in 0..50 {
+            let mut program: Vec<i64> = (0..len).map(|_| next()).collect();
+            program.extend([i64::MIN, i64::MAX, 0, -1]);
+            let memory = memory::Memory::from(program);
+
+            for radix in [2, 10, 16] {
+                let text = memory.to_string_radix(radix);
+                let parsed = memory::Memory::<i64>::from_str_radix(&text, radix).unwrap();
+                assert_eq!(parsed, memory);
+                assert_eq!(parsed.to_string_radix(radix), text);
+            }
+            assert_eq!(memory.to_string_radix(10), memory.to_string());
+        }
+
+        let big: memory::Memory<num::BigInt> = "-123456789012345678901234567890,7".parse().unwrap();
+        assert_eq!(big.to_string_radix(16), "-18ee90ff6c373e0ee4e3f0ad2,7");
+    }
+
+    #[test]
+    fn test_run_n_outputs() {
+        // outputs its 2 inputs, then their sum
+        let program = [3, 15, 3, 16, 4, 15, 4, 16, 1, 15, 16, 17, 4, 17, 99];
+        let mut vm = IntcodeVM::new(program);
+        vm.extend_input([20, 22]);
+        assert!(vm.run_n_outputs(0).unwrap().is_empty());
+        assert_eq!(vm.run_n_outputs(3).unwrap(), [20, 22, 42]);
+        assert!(matches!(
+            vm.run_n_outputs(1),
+            Err(error::VMError::HaltedBeforeOutput { collected: 0 })
+        ));
+
+        let mut vm = IntcodeVM::new(program);
+        vm.push_input(1);
+        assert!(matches!(
+            vm.run_n_outputs(2),
+            Err(error::VMError::UnexpectedInputRequest)
+        ));
+    }
+
+    #[test]
+    fn test_error_sources() {
+        fn boxed<E: std::error::Error + Send + Sync + 'static>(
+            err: E,
+        ) -> Box<dyn std::error::Error + Send + Sync> {
+            Box::new(err)
+        }
+
+        let err = boxed(IntcodeVM::new([15]).run().unwrap_err());
+        assert_eq!(
+            err.to_string(),
+            "The instruction 15 at address 0 was not any of [01, 02, 03, 04, 05, 06, 07, 08, 09, 99]"
+        );
+        assert!(err.source().is_none());
+
+        let err = boxed(memory::Memory::<i64>::parse_bytes(b"1,x,99").unwrap_err());
+        let source = err.source().unwrap();
+        assert!(source.is::<std::num::ParseIntError>());
+        assert_eq!(
+            err.to_string(),
+            format!("Could not parse the program: {source}")
+        );
+
+        let err = boxed(memory::Memory::<i32>::from_str_radix("1,0,0,3,6g", 16).unwrap_err());
+        assert!(err.source().unwrap().is::<std::num::ParseIntError>());
+
+        let err = IntcodeVM::<i64>::parse_many("1,0,0,3,99\n1,y")
+            .find_map(Result::err)
+            .map(boxed)
+            .unwrap();
+        assert!(err
+            .to_string()
+            .starts_with("Could not parse the program at line 2"));
+        assert!(err.source().unwrap().is::<std::num::ParseIntError>());
+    }
 }
@@ -1,6 +1,28 @@
-use std::{ops::Index, str::FromStr};
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    iter::Peekable,
+    ops::Index,
+    str::FromStr,
+};
+#[cfg(feature = "std")]
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+};
 
-use num::{Integer, ToPrimitive};
+use alloc::{
+    borrow::ToOwned,
+    collections::{btree_map, BTreeMap, BTreeSet},
+    string::String,
+    vec::Vec,
+};
+
+use num::{FromPrimitive, Integer, Num, ToPrimitive};
+
+#[cfg(feature = "std")]
+use crate::error::FromFileError;
+use crate::error::{FromBytesError, MemoryParseError};
 
 /// Represents a [VM's](crate::vm::IntcodeVM) memory
 ///
@@ -14,19 +36,214 @@ use num::{Integer, ToPrimitive};
 /// // do something with it before passing it to the VM
 /// let vm = IntcodeVM::new(memory);
 /// ```
+///
+/// The cells are stored contiguously by default, a [sparse](Memory::sparse) memory
+/// can be used instead for programs writing to a few far away addresses.
 #[derive(Debug, Clone)]
 pub struct Memory<T>
 where
     T: Integer + Clone + ToPrimitive,
 {
-    zero: T,
-    mem: Vec<T>,
+    /// Value of the cells that are not stored (0 unless set by [`Memory::with_fill()`](Memory::with_fill))
+    fill: T,
+    mem: Storage<T>,
+    highest_written_address: Option<usize>,
+}
+
+/// How the cells of a [Memory] are stored
+#[derive(Debug, Clone)]
+enum Storage<T> {
+    /// Every cell up to the highest one stored, the gaps filled with the fill value (0 by default)
+    Dense(Vec<T>),
+    /// Only the cells that were set, along with the length the memory would have if it was dense
+    Sparse {
+        cells: BTreeMap<usize, T>,
+        len: usize,
+    },
+}
+
+/// [Iterator] over the cells of a sparse [Memory], the cells that are not stored reading as its fill value
+struct SparseCells<'m, T> {
+    cells: Peekable<btree_map::Iter<'m, usize, T>>,
+    fill: &'m T,
+    address: usize,
+    len: usize,
+}
+
+impl<'m, T> Iterator for SparseCells<'m, T> {
+    type Item = &'m T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.address >= self.len {
+            return None;
+        }
+
+        let cell = match self.cells.next_if(|(&address, _)| address == self.address) {
+            Some((_, value)) => value,
+            None => self.fill,
+        };
+        self.address += 1;
+        Some(cell)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.address;
+        (remaining, Some(remaining))
+    }
 }
 
 impl<T> Memory<T>
 where
     T: Integer + Clone + ToPrimitive,
 {
+    /// Creates a memory holding the values of `vec`, without copying them
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let program = vec![1, 0, 0, 3, 99];
+    /// let address = program.as_ptr();
+    ///
+    /// let memory = Memory::from_vec(program);
+    /// assert_eq!(memory.as_slice().unwrap().as_ptr(), address);
+    /// ```
+    #[inline]
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        Self {
+            fill: T::zero(),
+            mem: Storage::Dense(vec),
+            highest_written_address: None,
+        }
+    }
+
+    /// Creates a memory holding the values of `mem`, whose cells that are not stored read as `fill` instead of 0
+    ///
+    /// This applies to every method mentioning the cells that are not stored as 0
+    /// (such as [`Memory::get()`](Memory::get), [`Memory::set()`](Memory::set) filling the gaps
+    /// or [`Memory::memory_starts_with()`](Memory::memory_starts_with)).
+    /// The instructions of a [VM](crate::vm::IntcodeVM) still write plain 0s (such as a false comparison).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let mut memory = Memory::with_fill(vec![1, 0, 0, 3, 99], -1);
+    /// assert_eq!(memory.get(10), &-1);
+    ///
+    /// memory.set(7, 2);
+    /// assert!(memory.memory_starts_with(&[1, 0, 0, 3, 99, -1, -1, 2, -1]));
+    /// ```
+    #[inline]
+    pub fn with_fill(mem: Vec<T>, fill: T) -> Self {
+        Self {
+            fill,
+            mem: Storage::Dense(mem),
+            highest_written_address: None,
+        }
+    }
+
+    /// Value of the cells that are not stored (0 unless set by [`Memory::with_fill()`](Memory::with_fill))
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// assert_eq!(Memory::from([1, 0, 0, 3, 99]).fill(), &0);
+    /// assert_eq!(Memory::with_fill(vec![99], -1).fill(), &-1);
+    /// ```
+    #[inline]
+    pub const fn fill(&self) -> &T {
+        &self.fill
+    }
+
+    /// Creates a memory holding a copy of the values of `slice`
+    ///
+    /// Every value is cloned, which is a plain copy for the primitive integers
+    /// but allocates for big integers (such as [`num::BigInt`]).
+    /// Use [`Memory::from_vec()`](Memory::from_vec) when the values are already owned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let program = [1, 0, 0, 3, 99];
+    /// let memory = Memory::from_slice(&program[..3]);
+    /// assert_eq!(memory.as_slice(), Some(&[1, 0, 0][..]));
+    /// ```
+    #[inline]
+    pub fn from_slice(slice: &[T]) -> Self {
+        Self::from_vec(slice.to_vec())
+    }
+
+    /// Creates an empty memory only storing the cells that are set,
+    /// rather than every cell up to the highest one
+    ///
+    /// It behaves just like the default (dense) memory, the cells that are not stored
+    /// reading as 0, but writing to a far away address does not allocate every cell before it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let mut memory = Memory::sparse();
+    /// memory.set(1_000_000, 5);
+    ///
+    /// assert!(memory.is_sparse());
+    /// assert_eq!(memory.get(1_000_000), &5);
+    /// assert_eq!(memory.get(10), &0);
+    /// assert_eq!(memory.len(), 1_000_001);
+    /// ```
+    #[inline]
+    pub fn sparse() -> Self {
+        Self {
+            fill: T::zero(),
+            mem: Storage::Sparse {
+                cells: BTreeMap::new(),
+                len: 0,
+            },
+            highest_written_address: None,
+        }
+    }
+
+    /// Converts this memory to a [sparse](Memory::sparse) one with the same content (and length),
+    /// only the cells that are not the fill value (0 by default) being kept
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{memory::Memory, IntcodeVM, VMResult};
+    /// let memory = Memory::from([1101, 2, 3, 1_000_000, 99]).into_sparse();
+    /// let mut vm = IntcodeVM::new(memory);
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// assert_eq!(vm.into_memory().get(1_000_000), &5);
+    ///
+    /// assert_eq!(Memory::from([1, 0, 0]).into_sparse().len(), 3);
+    /// ```
+    pub fn into_sparse(self) -> Self {
+        let mem = match self.mem {
+            Storage::Dense(mem) => Storage::Sparse {
+                len: mem.len(),
+                cells: mem
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(_, value)| *value != self.fill)
+                    .collect(),
+            },
+            sparse @ Storage::Sparse { .. } => sparse,
+        };
+
+        Self { mem, ..self }
+    }
+
+    /// Whether this memory is [sparse](Memory::sparse)
+    #[inline]
+    pub fn is_sparse(&self) -> bool {
+        matches!(self.mem, Storage::Sparse { .. })
+    }
+
     /// Returns a reference to the value at `address` in the memory
     ///
     /// # Note
@@ -61,7 +278,7 @@ where
     /// ```
     #[inline]
     pub fn get(&self, address: usize) -> &T {
-        self.mem.get(address).unwrap_or(&self.zero)
+        self.try_peek(address).unwrap_or(&self.fill)
     }
 
     /// Replaces the value at `address` with `value`
@@ -93,16 +310,200 @@ where
     /// ```
     #[inline]
     pub fn set(&mut self, address: usize, value: T) {
-        if let Some(existing) = self.mem.get_mut(address) {
-            *existing = value;
-        } else {
-            self.mem.resize(address, self.zero.clone());
-            self.mem.push(value);
+        self.highest_written_address = self.highest_written_address.max(Some(address));
+        match &mut self.mem {
+            Storage::Dense(mem) => {
+                if let Some(existing) = mem.get_mut(address) {
+                    *existing = value;
+                } else {
+                    mem.resize(address, self.fill.clone());
+                    mem.push(value);
+                }
+            }
+            Storage::Sparse { cells, len } => {
+                cells.insert(address, value);
+                *len = (*len).max(address + 1);
+            }
         }
     }
 
+    /// Value of the cell at `address` (0 if it is not stored), like [`Memory::get()`](Memory::get) but cloned
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let memory = Memory::from([1, 0, 0, 3, 99]);
+    ///
+    /// assert_eq!(memory.peek(3), 3);
+    /// assert_eq!(memory.peek(10), 0);
+    /// ```
+    #[inline]
+    pub fn peek(&self, address: usize) -> T {
+        self.get(address).clone()
+    }
+
+    /// Replaces the value at `address` with `value`, exactly like [`Memory::set()`](Memory::set)
+    #[inline]
+    pub fn poke(&mut self, address: usize, value: T) {
+        self.set(address, value);
+    }
+
+    /// Value of the cell at `address` if it is stored, [`None`] if it was never written
+    ///
+    /// This tells a cell explicitly set to the fill value (0 by default) from an untouched one.
+    /// A dense memory stores every cell below its [length](Memory::len)
+    /// (the gaps being filled with the fill value), only a [sparse](Memory::sparse) memory leaves the gaps unstored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let mut memory = Memory::sparse();
+    /// memory.set(3, 0);
+    /// memory.set(10, 7);
+    ///
+    /// assert_eq!(memory.try_peek(3), Some(&0));
+    /// assert_eq!(memory.try_peek(10), Some(&7));
+    /// assert_eq!(memory.try_peek(5), None);
+    /// assert_eq!(memory.try_peek(11), None);
+    ///
+    /// let mut memory = Memory::from([1, 0, 0, 3, 99]);
+    /// memory.set(10, 7);
+    /// assert_eq!(memory.try_peek(5), Some(&0));
+    /// assert_eq!(memory.try_peek(11), None);
+    /// ```
+    #[inline]
+    pub fn try_peek(&self, address: usize) -> Option<&T> {
+        match &self.mem {
+            Storage::Dense(mem) => mem.get(address),
+            Storage::Sparse { cells, .. } => cells.get(&address),
+        }
+    }
+
+    /// Number of cells explicitly stored (the cells beyond them all being 0)
+    ///
+    /// A [sparse](Memory::sparse) memory has the same length as the dense memory with the same content would have,
+    /// even though it does not store every cell.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let mut memory = Memory::from([1, 0, 0, 3, 99]);
+    /// assert_eq!(memory.len(), 5);
+    ///
+    /// memory.set(10, 2);
+    /// assert_eq!(memory.len(), 11);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        match &self.mem {
+            Storage::Dense(mem) => mem.len(),
+            Storage::Sparse { len, .. } => *len,
+        }
+    }
+
+    /// Whether no cell is explicitly stored
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        match &self.mem {
+            Storage::Dense(mem) => mem.is_empty(),
+            Storage::Sparse { len, .. } => *len == 0,
+        }
+    }
+
+    /// Grows (with the fill value, 0 by default) or truncates the memory so that its [length](Memory::len) is `len`
+    ///
+    /// The cells truncated away read as the fill value (0 by default) afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let mut memory = Memory::from([1, 0, 0, 3, 99]);
+    /// memory.resize_to(8);
+    /// assert!(memory.memory_starts_with(&[1, 0, 0, 3, 99, 0, 0, 0]));
+    /// assert_eq!(memory.len(), 8);
+    ///
+    /// memory.resize_to(2);
+    /// assert_eq!(memory.len(), 2);
+    /// assert_eq!(memory.get(4), &0);
+    /// ```
+    pub fn resize_to(&mut self, len: usize) {
+        match &mut self.mem {
+            Storage::Dense(mem) => mem.resize(len, self.fill.clone()),
+            Storage::Sparse {
+                cells,
+                len: sparse_len,
+            } => {
+                cells.split_off(&len);
+                *sparse_len = len;
+            }
+        }
+    }
+
+    /// Drops the 0s at the end of the memory and releases the unused capacity of its storage
+    ///
+    /// The content of the memory is not modified (the dropped cells read as 0).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let mut memory = Memory::from([1, 0, 0, 3, 99]);
+    /// memory.set(1_000_000, 0);
+    /// assert_eq!(memory.len(), 1_000_001);
+    ///
+    /// memory.shrink_to_fit();
+    /// assert_eq!(memory.len(), 5);
+    /// assert_eq!(memory.get(1_000_000), &0);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        match &mut self.mem {
+            Storage::Dense(mem) => {
+                let len = mem
+                    .iter()
+                    .rposition(|value| *value != self.fill)
+                    .map_or(0, |last| last + 1);
+                mem.truncate(len);
+                mem.shrink_to_fit();
+            }
+            Storage::Sparse { cells, len } => {
+                while cells
+                    .last_key_value()
+                    .is_some_and(|(_, value)| *value == self.fill)
+                {
+                    cells.pop_last();
+                }
+                *len = cells.last_key_value().map_or(0, |(&last, _)| last + 1);
+            }
+        }
+    }
+
+    /// Highest address passed to [`Memory::set()`](Memory::set), [`None`] if it was never called
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let mut memory = Memory::from([1, 0, 0, 3, 99]);
+    /// assert_eq!(memory.highest_written_address(), None);
+    ///
+    /// memory.set(10, 2);
+    /// memory.set(3, 2);
+    /// assert_eq!(memory.highest_written_address(), Some(10));
+    /// ```
+    #[inline]
+    pub fn highest_written_address(&self) -> Option<usize> {
+        self.highest_written_address
+    }
+
     /// Creates an [iterator](Iterator) over the memory
     ///
+    /// Every cell up to [`Memory::len()`](Memory::len) is iterated over,
+    /// including the ones a [sparse](Memory::sparse) memory does not store.
+    ///
     /// # Example
     ///
     /// ```
@@ -119,7 +520,130 @@ where
     /// ```
     #[inline]
     pub fn iter(&self) -> impl Iterator<Item = &T> {
-        self.mem.iter()
+        let (dense, sparse) = match &self.mem {
+            Storage::Dense(mem) => (Some(mem.iter()), None),
+            Storage::Sparse { cells, len } => (
+                None,
+                Some(SparseCells {
+                    cells: cells.iter().peekable(),
+                    fill: &self.fill,
+                    address: 0,
+                    len: *len,
+                }),
+            ),
+        };
+
+        dense
+            .into_iter()
+            .flatten()
+            .chain(sparse.into_iter().flatten())
+    }
+
+    /// Creates an [iterator](Iterator) over mutable references to the memory
+    ///
+    /// Only the cells explicitly stored are iterated over
+    /// (for a [sparse](Memory::sparse) memory, the cells that were set, in increasing address order).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let mut memory = Memory::from([1, 12, 2, 3, 99]);
+    /// for cell in memory.iter_mut().filter(|cell| **cell == 12) {
+    ///     *cell = 0;
+    /// }
+    ///
+    /// assert!(memory.memory_starts_with(&[1, 0, 2, 3, 99]));
+    /// ```
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        let (dense, sparse) = match &mut self.mem {
+            Storage::Dense(mem) => (Some(mem.iter_mut()), None),
+            Storage::Sparse { cells, .. } => (None, Some(cells.values_mut())),
+        };
+
+        dense
+            .into_iter()
+            .flatten()
+            .chain(sparse.into_iter().flatten())
+    }
+
+    /// Every cell whose value differs between this memory and `other`, as `(address, value here, value in other)`
+    /// in increasing address order (the cells that are not stored reading as 0)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{memory::Memory, IntcodeVM, VMResult};
+    /// let before = Memory::from([1, 0, 0, 3, 99]);
+    /// let mut vm = IntcodeVM::new(before.clone());
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    ///
+    /// assert_eq!(before.diff(&vm.into_memory()), [(3, 3, 2)]);
+    /// assert_eq!(Memory::from([1, 2]).diff(&Memory::from([1, 2, 0, 4])), [(3, 0, 4)]);
+    /// ```
+    pub fn diff(&self, other: &Memory<T>) -> Vec<(usize, T, T)> {
+        let differs = |address: &usize| self.get(*address) != other.get(*address);
+        let addresses: Vec<_> = if self.is_sparse() || other.is_sparse() {
+            let stored: BTreeSet<_> = self
+                .stored_addresses()
+                .chain(other.stored_addresses())
+                .collect();
+            stored.into_iter().filter(differs).collect()
+        } else {
+            (0..self.len().max(other.len())).filter(differs).collect()
+        };
+
+        addresses
+            .into_iter()
+            .map(|address| {
+                (
+                    address,
+                    self.get(address).clone(),
+                    other.get(address).clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the cells explicitly stored as a slice,
+    /// [`None`] if the memory is [sparse](Memory::sparse) (its cells not being contiguous)
+    ///
+    /// The slice only covers the first [`Memory::len()`](Memory::len) cells, the ones beyond
+    /// (which read as 0 through [`Memory::get()`](Memory::get)) are not part of it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let memory = Memory::from([1, 0, 0, 3, 99]);
+    ///
+    /// assert_eq!(memory.as_slice(), Some(&[1, 0, 0, 3, 99][..]));
+    /// assert_eq!(memory.get(10), &0);
+    /// assert_eq!(memory.as_slice().unwrap().get(10), None);
+    ///
+    /// assert_eq!(memory.into_sparse().as_slice(), None);
+    /// ```
+    #[inline]
+    pub fn as_slice(&self) -> Option<&[T]> {
+        match &self.mem {
+            Storage::Dense(mem) => Some(mem),
+            Storage::Sparse { .. } => None,
+        }
+    }
+
+    /// Addresses of the cells explicitly stored, in increasing order
+    #[inline]
+    fn stored_addresses(&self) -> impl Iterator<Item = usize> + '_ {
+        let (dense, sparse) = match &self.mem {
+            Storage::Dense(mem) => (Some(0..mem.len()), None),
+            Storage::Sparse { cells, .. } => (None, Some(cells.keys().copied())),
+        };
+
+        dense
+            .into_iter()
+            .flatten()
+            .chain(sparse.into_iter().flatten())
     }
 
     /// Checks if this memory's first `n` elements are the same as the `n` elements of `iter`
@@ -146,19 +670,378 @@ where
     /// ```
     #[inline]
     pub fn memory_starts_with<'t, I>(&self, iter: I) -> bool
+    where
+        T: 't,
+        I: IntoIterator<Item = &'t T>,
+    {
+        self.first_mismatch(iter).is_none()
+    }
+
+    /// Finds the first element of `iter` that differs from the cell at the same address in this memory,
+    /// returning its address, the value in this memory and the one in `iter` (the cells beyond the memory being 0)
+    ///
+    /// Returns [`None`] if this memory starts with the elements of `iter`
+    /// (see [`Memory::memory_starts_with()`](Memory::memory_starts_with)).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let memory = Memory::from([1, 0, 0, 3, 99]);
+    ///
+    /// assert_eq!(memory.first_mismatch(&[1, 0, 0, 3]), None);
+    /// assert_eq!(memory.first_mismatch(&[1, 0, 0, 2, 99]), Some((3, 3, 2)));
+    /// assert_eq!(memory.first_mismatch(&[1, 0, 0, 3, 99, 0, 7]), Some((6, 0, 7)));
+    /// ```
+    pub fn first_mismatch<'t, I>(&self, iter: I) -> Option<(usize, T, T)>
     where
         T: 't,
         I: IntoIterator<Item = &'t T>,
     {
         let mut self_iter = self.iter().fuse();
-        iter.into_iter().all(|iter_val| {
-            self_iter
-                .next()
-                .map_or_else(|| iter_val.is_zero(), |self_val| self_val.eq(iter_val))
-        })
+        iter.into_iter()
+            .enumerate()
+            .find_map(|(address, iter_val)| {
+                let self_val = self_iter.next().unwrap_or(&self.fill);
+                (self_val != iter_val).then(|| (address, self_val.clone(), iter_val.clone()))
+            })
+    }
+
+    /// Parses a comma separated list of values written in base `radix`
+    /// (the fields are split as per [`Memory::from_str()`](Memory::from_str))
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in the range from 2 to 36
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::MemoryParseError, memory::Memory};
+    /// let memory = Memory::<i32>::from_str_radix("1,0,0,3,63", 16).unwrap();
+    /// assert!(memory.memory_starts_with(&[1, 0, 0, 3, 99]));
+    ///
+    /// let err = Memory::<i32>::from_str_radix("1,0,0,3,6g", 16).unwrap_err();
+    /// assert!(matches!(err, MemoryParseError::Invalid { token, .. } if token == "6g"));
+    /// ```
+    pub fn from_str_radix(
+        s: &str,
+        radix: u32,
+    ) -> Result<Self, MemoryParseError<<T as Num>::FromStrRadixErr>> {
+        fields(s)
+            .map(|token| {
+                T::from_str_radix(token, radix).map_err(|source| MemoryParseError::Invalid {
+                    token: token.to_owned(),
+                    source,
+                })
+            })
+            .collect()
+    }
+
+    /// Writes the cells explicitly stored (as per [`Memory::iter()`](Memory::iter)) in base `radix`, separated by commas,
+    /// in the format parsed by [`Memory::from_str_radix()`](Memory::from_str_radix)
+    ///
+    /// The digits above 9 are written as lowercase letters, negative values are prefixed by `-`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in the range from 2 to 36
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let memory = Memory::from([1, 0, 0, 3, 99, -255]);
+    /// assert_eq!(memory.to_string_radix(16), "1,0,0,3,63,-ff");
+    /// assert_eq!(Memory::from_str_radix(&memory.to_string_radix(2), 2).unwrap(), memory);
+    /// ```
+    pub fn to_string_radix(&self, radix: u32) -> String
+    where
+        T: FromPrimitive,
+    {
+        assert!(
+            (2..=36).contains(&radix),
+            "radix must be in the range from 2 to 36 (was {radix})"
+        );
+        let base = T::from_u32(radix).expect("every integer type can hold a number up to 36");
+
+        let mut text = String::new();
+        let mut digits = Vec::new();
+        for (address, value) in self.iter().enumerate() {
+            if address > 0 {
+                text.push(',');
+            }
+
+            // the remainders have the sign of the value, which is never negated (it could overflow)
+            let mut value = value.clone();
+            if value < T::zero() {
+                text.push('-');
+            }
+            loop {
+                let (quotient, remainder) = value.div_rem(&base);
+                let digit = remainder.to_i64().map_or(0, i64::unsigned_abs) as u32;
+                digits.push(char::from_digit(digit, radix).expect("the remainder is a digit"));
+                value = quotient;
+                if value.is_zero() {
+                    break;
+                }
+            }
+            text.extend(digits.drain(..).rev());
+        }
+
+        text
+    }
+}
+
+impl<T> Memory<T>
+where
+    T: Integer + Clone + ToPrimitive + FromStr,
+{
+    /// Reads the file at `path` and parses its content (as per [`Memory::from_str()`](Memory::from_str))
+    /// after trimming the surrounding whitespace
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use intcode_vm::memory::Memory;
+    /// let memory: Memory<i64> = Memory::from_file("input/2019/day2.txt").unwrap();
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, FromFileError<<T as FromStr>::Err>> {
+        std::fs::read_to_string(path)?
+            .trim()
+            .parse()
+            .map_err(FromFileError::Parse)
+    }
+
+    /// Reads a comma separated list of values (as per [`Memory::from_str()`](Memory::from_str))
+    /// from `reader`, parsing the values as they are read
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`InvalidData`](io::ErrorKind::InvalidData) if a value cannot be parsed,
+    /// or any error returned by `reader`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let memory: Memory<i32> = Memory::from_reader("1,0,0,3,99\n".as_bytes()).unwrap();
+    /// assert!(memory.memory_starts_with(&[1, 0, 0, 3, 99]));
+    ///
+    /// assert!(Memory::<i32>::from_reader("1,x,99".as_bytes()).is_err());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: Read>(reader: R) -> io::Result<Self> {
+        let mut mem = Vec::new();
+        let mut trailing_empty_fields = 0;
+        for field in BufReader::new(reader).split(b',') {
+            let field = field?;
+            let field = std::str::from_utf8(&field)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            for value in comma_field_values(field) {
+                if value.is_empty() {
+                    trailing_empty_fields += 1;
+                    continue;
+                }
+
+                if trailing_empty_fields > 0 {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "empty value in the program",
+                    ));
+                }
+
+                mem.push(value.parse().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("could not parse {value:?}"),
+                    )
+                })?);
+            }
+        }
+
+        Ok(Self::from(mem))
+    }
+
+    /// Parses a comma separated list of values (as per [`Memory::from_str()`](Memory::from_str))
+    /// from UTF-8 encoded bytes (such as the ones of [`include_bytes!`])
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::FromBytesError, memory::Memory};
+    /// let memory = Memory::<i32>::parse_bytes(b"1,0,0,3,99\n").unwrap();
+    /// assert!(memory.memory_starts_with(&[1, 0, 0, 3, 99]));
+    ///
+    /// let err = Memory::<i32>::parse_bytes(b"1,\xff,99").unwrap_err();
+    /// assert!(matches!(err, FromBytesError::Utf8(_)));
+    /// ```
+    #[inline]
+    pub fn parse_bytes(bytes: &[u8]) -> Result<Self, FromBytesError<<T as FromStr>::Err>> {
+        core::str::from_utf8(bytes)?
+            .parse()
+            .map_err(FromBytesError::Parse)
+    }
+
+    /// Parses a comma separated list of values (as per [`Memory::from_str()`](Memory::from_str)),
+    /// returning a [`MemoryParseError::Overflow`] suggesting a wider type
+    /// when a value is a valid integer too big (or too small) for `T`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::MemoryParseError, memory::Memory};
+    /// let err = Memory::<i32>::parse_with_overflow_hint("104,4611686018427387904,99").unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "value 4611686018427387904 overflows i32; consider i64"
+    /// );
+    ///
+    /// let err = Memory::<i32>::parse_with_overflow_hint("104,four,99").unwrap_err();
+    /// assert!(matches!(err, MemoryParseError::Invalid { .. }));
+    /// ```
+    pub fn parse_with_overflow_hint(
+        s: &str,
+    ) -> Result<Self, MemoryParseError<<T as FromStr>::Err>> {
+        fields(s)
+            .map(|token| {
+                token
+                    .parse::<T>()
+                    .map_err(|source| match overflow_suggestion(token) {
+                        Some(suggested_type) => MemoryParseError::Overflow {
+                            token: token.to_owned(),
+                            type_name: core::any::type_name::<T>(),
+                            suggested_type,
+                        },
+                        None => MemoryParseError::Invalid {
+                            token: token.to_owned(),
+                            source,
+                        },
+                    })
+            })
+            .collect()
+    }
+
+    /// Parses a comma separated list of values (as per [`Memory::from_str()`](Memory::from_str)),
+    /// returning every field that cannot be parsed (as its index and its trimmed text) instead of the first one
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::memory::Memory;
+    /// let memory = Memory::<i32>::parse_verbose("1,0,0,3,99").unwrap();
+    /// assert!(memory.memory_starts_with(&[1, 0, 0, 3, 99]));
+    ///
+    /// let errors = Memory::<i32>::parse_verbose("1,x,0, 3y ,99").unwrap_err();
+    /// assert_eq!(errors, [(1, "x".to_owned()), (3, "3y".to_owned())]);
+    /// ```
+    pub fn parse_verbose(s: &str) -> Result<Self, Vec<(usize, String)>> {
+        let mut mem = Vec::new();
+        let mut errors = Vec::new();
+        for (index, token) in fields(s).enumerate() {
+            match token.parse() {
+                Ok(value) => mem.push(value),
+                Err(_) => errors.push((index, token.to_owned())),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(Self::from(mem))
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Fields of `s` separated by commas or whitespace, without the empty fields at the end
+///
+/// A run of whitespace (around a comma or not) is a single separator,
+/// but two commas in a row still delimit an empty field.
+fn fields(s: &str) -> impl Iterator<Item = &str> {
+    let mut fields: Vec<_> = s.split(',').flat_map(comma_field_values).collect();
+    while fields.last().is_some_and(|field| field.is_empty()) {
+        fields.pop();
+    }
+
+    fields.into_iter()
+}
+
+/// Values of a field of a comma separated list, separated by whitespace (a single empty value if there is none)
+fn comma_field_values(field: &str) -> impl Iterator<Item = &str> {
+    let empty = field.trim().is_empty();
+    empty
+        .then_some("")
+        .into_iter()
+        .chain(field.split_whitespace())
+}
+
+/// Smallest signed integer type able to hold `token`, [`None`] if `token` is not an integer
+fn overflow_suggestion(token: &str) -> Option<&'static str> {
+    let digits = token.strip_prefix(['-', '+']).unwrap_or(token);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
     }
+
+    Some(match token.parse::<i128>() {
+        Ok(value) if i8::try_from(value).is_ok() => "i8",
+        Ok(value) if i16::try_from(value).is_ok() => "i16",
+        Ok(value) if i32::try_from(value).is_ok() => "i32",
+        Ok(value) if i64::try_from(value).is_ok() => "i64",
+        Ok(_) => "i128",
+        Err(_) => "num::BigInt",
+    })
 }
 
+pub(crate) use sealed::MemoryRead;
+
+/// Cells a [VM](crate::IntcodeVM) executes its program from: a [Memory],
+/// or the write overlay of a [VmView](crate::view::VmView) over a borrowed one
+///
+/// This trait is sealed, it cannot be implemented outside of this crate.
+pub trait MemoryStore<T>: sealed::Store<T> {}
+
+pub(crate) mod sealed {
+    /// Read access to the cells of a memory, missing cells reading as 0
+    pub trait MemoryRead<T> {
+        fn get(&self, address: usize) -> &T;
+    }
+
+    /// Write access to the cells of a memory, as needed by the VM
+    pub trait Store<T>: MemoryRead<T> + Clone {
+        fn set(&mut self, address: usize, value: T);
+
+        fn len(&self) -> usize;
+    }
+}
+
+impl<T> MemoryRead<T> for Memory<T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    #[inline]
+    fn get(&self, address: usize) -> &T {
+        Memory::get(self, address)
+    }
+}
+
+impl<T> sealed::Store<T> for Memory<T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    #[inline]
+    fn set(&mut self, address: usize, value: T) {
+        Memory::set(self, address, value);
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        Memory::len(self)
+    }
+}
+
+impl<T> MemoryStore<T> for Memory<T> where T: Integer + Clone + ToPrimitive {}
+
 impl<T> Index<usize> for Memory<T>
 where
     T: Integer + Clone + ToPrimitive,
@@ -171,14 +1054,106 @@ where
     }
 }
 
+/// Two memories are equal if every cell reads the same in both,
+/// the cells that are not stored reading as 0 (or the [fill value](Memory::with_fill))
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::memory::Memory;
+/// assert_eq!(Memory::from([1, 2, 0, 0]), Memory::from([1, 2]));
+/// assert_eq!(Memory::from([0, 0]), Memory::from([]));
+///
+/// assert_ne!(Memory::from([1, 2, 0, 3]), Memory::from([1, 2]));
+/// assert_ne!(Memory::from([0, 1]), Memory::from([1]));
+/// ```
+impl<T> PartialEq for Memory<T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.fill == other.fill
+            && self
+                .stored_addresses()
+                .chain(other.stored_addresses())
+                .all(|address| self.get(address) == other.get(address))
+    }
+}
+
+impl<T> Eq for Memory<T> where T: Integer + Clone + ToPrimitive + Eq {}
+
+/// Hashes the cells that are not 0 (or the [fill value](Memory::with_fill)) along with their addresses, consistently with [PartialEq]:
+/// memories only differing by their trailing 0s (or by being [sparse](Memory::sparse)) hash the same
+///
+/// # Example
+///
+/// ```
+/// # use std::collections::HashSet;
+/// # use intcode_vm::memory::Memory;
+/// let mut seen = HashSet::new();
+/// seen.insert(Memory::from([1, 0, 0, 3, 99]));
+///
+/// assert!(seen.contains(&Memory::from([1, 0, 0, 3, 99, 0, 0])));
+/// assert!(seen.contains(&Memory::from([1, 0, 0, 3, 99]).into_sparse()));
+/// assert!(!seen.contains(&Memory::from([1, 0, 0, 3, 98])));
+/// ```
+impl<T> Hash for Memory<T>
+where
+    T: Integer + Clone + ToPrimitive + Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for address in self.stored_addresses() {
+            let value = self.get(address);
+            if *value != self.fill {
+                address.hash(state);
+                value.hash(state);
+            }
+        }
+    }
+}
+
+/// Writes the cells explicitly stored (as per [`Memory::iter()`](Memory::iter)) separated by commas,
+/// in the format parsed by [`Memory::from_str()`](Memory::from_str)
+///
+/// The implicit zeros after the last stored cell are not written
+/// (the cells a [sparse](Memory::sparse) memory does not store before its [length](Memory::len) are).
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::memory::Memory;
+/// let mut memory = Memory::from([1, 0, 0, 3, 99]);
+/// memory.set(6, -1);
+/// assert_eq!(memory.to_string(), "1,0,0,3,99,0,-1");
+///
+/// assert_eq!(memory.to_string().parse::<Memory<i32>>().unwrap(), memory);
+/// ```
+impl<T> fmt::Display for Memory<T>
+where
+    T: Integer + Clone + ToPrimitive + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (address, value) in self.iter().enumerate() {
+            if address > 0 {
+                f.write_str(",")?;
+            }
+
+            write!(f, "{value}")?;
+        }
+
+        Ok(())
+    }
+}
+
 impl<T> FromIterator<T> for Memory<T>
 where
     T: Integer + Clone + ToPrimitive,
 {
     fn from_iter<IT: IntoIterator<Item = T>>(iter: IT) -> Self {
         Self {
-            zero: T::zero(),
-            mem: iter.into_iter().collect(),
+            fill: T::zero(),
+            mem: Storage::Dense(iter.into_iter().collect()),
+            highest_written_address: None,
         }
     }
 }
@@ -200,8 +1175,11 @@ where
 {
     type Err = <T as FromStr>::Err;
 
-    /// Parses a comma separated list of values **WITHOUT SPACES**
-    /// (unless [`T::from_str()`](FromStr) ignores them)
+    /// Parses a comma separated list of values
+    ///
+    /// The whitespace around the values is ignored, as are empty values at the end of the list
+    /// (such as the one after a trailing comma).
+    /// Whitespace between two values also separates them, so space or newline separated programs parse too.
     ///
     /// # Example
     ///
@@ -210,9 +1188,10 @@ where
     /// let memory: Memory<i32> = "1,0,0,3,99".parse().unwrap();
     ///
     /// assert!(memory.memory_starts_with([1, 0, 0, 3, 99].iter()));
+    /// assert_eq!("1 0 0\n3, 99".parse::<Memory<i32>>().unwrap(), memory);
     /// ```
     #[inline]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        s.split(',').map(|part| part.parse::<T>()).collect()
+        fields(s).map(|part| part.parse::<T>()).collect()
     }
 }
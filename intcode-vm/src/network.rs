@@ -0,0 +1,182 @@
+//! Helpers running several VMs communicating with each other (such as the amplifiers of day 7)
+
+use alloc::{vec, vec::Vec};
+
+use num::{Integer, ToPrimitive};
+
+use crate::{
+    error::{self, VMError},
+    memory::Memory,
+    IntcodeVM, VMResult,
+};
+
+/// Runs a chain of amplifiers (one VM per phase, each receiving its phase as first input)
+/// where the output of each amplifier is the input of the next one, `initial_input` being given to the first one
+///
+/// Every amplifier has its own input queue, receiving the outputs of the previous amplifier in order.
+/// The outputs of the last amplifier are fed back to the first one as long as it is still running
+/// (the feedback loop of day 7 part 2), the chain stops once every amplifier halted.
+///
+/// Returns the last output of the last amplifier (or `initial_input` if it never outputted anything)
+///
+/// # Errors
+///
+/// Returns [`VMError::PipelineDeadlock`] if a whole round over the amplifiers went by
+/// without any of them receiving an input, producing an output or halting
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::{memory::Memory, network::run_amplifier_chain};
+/// // feed-forward
+/// let program: Memory<i64> = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0".parse().unwrap();
+/// assert_eq!(run_amplifier_chain(&program, &[4, 3, 2, 1, 0], 0).unwrap(), 43210);
+///
+/// // feedback loop
+/// let program: Memory<i64> = "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(run_amplifier_chain(&program, &[9, 8, 7, 6, 5], 0).unwrap(), 139629729);
+/// ```
+pub fn run_amplifier_chain<T>(
+    program: &Memory<T>,
+    phases: &[T],
+    initial_input: T,
+) -> error::Result<T, T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    let mut vms: Vec<_> = phases
+        .iter()
+        .map(|phase| {
+            let mut vm = IntcodeVM::new(program.clone());
+            vm.push_input(phase.clone());
+            Some(vm)
+        })
+        .collect();
+
+    let count = vms.len();
+    let mut last_output = initial_input.clone();
+    if let Some(Some(first)) = vms.first_mut() {
+        first.push_input(initial_input);
+    }
+
+    while vms.iter().any(Option::is_some) {
+        let mut progressed = false;
+        for index in 0..count {
+            let Some(vm) = &mut vms[index] else {
+                continue;
+            };
+
+            let mut outputs = Vec::new();
+            loop {
+                match vm.run()? {
+                    VMResult::OutOfFuel => return Err(VMError::OutOfFuel),
+                    VMResult::WaitingForInput => break,
+                    VMResult::Output(out) => outputs.push(out),
+                    VMResult::Halted | VMResult::Paused => {
+                        vms[index] = None;
+                        progressed = true;
+                        break;
+                    }
+                    _ => (),
+                }
+            }
+
+            if let Some(out) = outputs.last() {
+                progressed = true;
+                if index == count - 1 {
+                    last_output = out.clone();
+                }
+            }
+
+            // the outputs of a halted amplifier's successor are lost
+            if let Some(next) = &mut vms[(index + 1) % count] {
+                next.extend_input(outputs);
+            }
+        }
+
+        if !progressed {
+            return Err(VMError::PipelineDeadlock {
+                waiting: vms
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, slot)| slot.is_some())
+                    .map(|(index, _)| index)
+                    .collect(),
+            });
+        }
+    }
+
+    Ok(last_output)
+}
+
+/// Every ordering of `items`
+fn permutations<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    if items.is_empty() {
+        return vec![Vec::new()];
+    }
+
+    let mut result = Vec::new();
+    for index in 0..items.len() {
+        let mut rest = items.to_vec();
+        let first = rest.remove(index);
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, first.clone());
+            result.push(permutation);
+        }
+    }
+
+    result
+}
+
+/// Highest signal an [amplifier chain](run_amplifier_chain) running `program`
+/// can produce (with 0 as initial input), trying every ordering of `phases`
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::{memory::Memory, network::max_amplifier_signal};
+/// let program: Memory<i64> = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0".parse().unwrap();
+/// assert_eq!(max_amplifier_signal(&program, &[0, 1, 2, 3, 4]).unwrap(), 43210);
+/// ```
+pub fn max_amplifier_signal<T>(program: &Memory<T>, phases: &[T]) -> error::Result<T, T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    let mut max: Option<T> = None;
+    for phases in permutations(phases) {
+        let signal = run_amplifier_chain(program, &phases, T::zero())?;
+        max = Some(match max {
+            Some(max) => max.max(signal),
+            None => signal,
+        });
+    }
+
+    Ok(max.unwrap_or_else(T::zero))
+}
+
+/// Same as [`max_amplifier_signal()`](max_amplifier_signal), trying the orderings of `phases` on separate threads
+///
+/// Every ordering runs on its own VMs, built from the shared `program`.
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::{memory::Memory, network::max_amplifier_signal_parallel};
+/// let program: Memory<i64> = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0".parse().unwrap();
+/// assert_eq!(max_amplifier_signal_parallel(&program, &[0, 1, 2, 3, 4]).unwrap(), 43210);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn max_amplifier_signal_parallel<T>(program: &Memory<T>, phases: &[T]) -> error::Result<T, T>
+where
+    T: Integer + Clone + ToPrimitive + Send + Sync,
+{
+    use rayon::prelude::*;
+
+    permutations(phases)
+        .into_par_iter()
+        .map(|phases| run_amplifier_chain(program, &phases, T::zero()))
+        .try_reduce_with(|a, b| Ok(a.max(b)))
+        .unwrap_or_else(|| Ok(T::zero()))
+}
@@ -0,0 +1,81 @@
+//! Helpers searching for the inputs making a program produce a given result (such as the noun and verb of day 2)
+
+use core::ops::RangeInclusive;
+
+use num::{Integer, ToPrimitive};
+
+use crate::{
+    error::{self, VMError},
+    memory::Memory,
+    IntcodeVM, VMResult,
+};
+
+/// Finds the first `(noun, verb)` pair (both taken from `range`, trying every verb for a noun before the next noun)
+/// for which `program`, with the noun at address 1 and the verb at address 2, halts with `target` at address 0
+///
+/// Every pair is tried on a fresh copy of `program`. Returns [`None`] if no pair matches.
+///
+/// # Errors
+///
+/// Returns [`VMError::UnexpectedInputRequest`] if the program asks for an input,
+/// or any error returned while running it
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::{memory::Memory, search::search_noun_verb};
+/// // adds the values at the noun and verb addresses, into address 0
+/// let program = Memory::from([1, 0, 0, 0, 99]);
+///
+/// assert_eq!(search_noun_verb(&program, 100, 0..=4).unwrap(), Some((0, 4)));
+/// assert_eq!(search_noun_verb(&program, 1000, 0..=4).unwrap(), None);
+/// ```
+pub fn search_noun_verb<T>(
+    program: &Memory<T>,
+    target: T,
+    range: RangeInclusive<T>,
+) -> error::Result<Option<(T, T)>, T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    let (start, end) = range.into_inner();
+    if start > end {
+        return Ok(None);
+    }
+
+    let mut noun = start.clone();
+    loop {
+        let mut verb = start.clone();
+        loop {
+            let mut memory = program.clone();
+            memory.set(1, noun.clone());
+            memory.set(2, verb.clone());
+
+            let mut vm = IntcodeVM::new(memory);
+            loop {
+                match vm.run()? {
+                    VMResult::OutOfFuel => return Err(VMError::OutOfFuel),
+                    VMResult::Output(_) => (),
+                    VMResult::WaitingForInput => return Err(VMError::UnexpectedInputRequest),
+                    VMResult::BreakpointHit(_) | VMResult::WatchpointHit { .. } => (),
+                    _ => break,
+                }
+            }
+
+            if vm.into_memory().get(0) == &target {
+                return Ok(Some((noun, verb)));
+            }
+
+            // checked before incrementing, the end of the range may be the largest value of `T`
+            if verb == end {
+                break;
+            }
+            verb = verb + T::one();
+        }
+
+        if noun == end {
+            return Ok(None);
+        }
+        noun = noun + T::one();
+    }
+}
@@ -0,0 +1,78 @@
+//! [serde] support for [Memory], [IntcodeVM] (and [VMResult](crate::VMResult), derived in place)
+
+use num::{Integer, ToPrimitive};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{memory::Memory, IntcodeVM};
+
+/// Serialized as the sequence of its cells
+impl<T> Serialize for Memory<T>
+where
+    T: Integer + Clone + ToPrimitive + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Memory<T>
+where
+    T: Integer + Clone + ToPrimitive + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(Memory::from)
+    }
+}
+
+#[derive(Serialize)]
+struct VMStateRef<'vm, T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    memory: &'vm Memory<T>,
+    instruction_pointer: usize,
+    relative_base: &'vm T,
+    inputs: Vec<&'vm T>,
+}
+
+#[derive(Deserialize)]
+struct VMState<T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    memory: Memory<T>,
+    instruction_pointer: usize,
+    relative_base: T,
+    inputs: Vec<T>,
+}
+
+/// Only the execution state is serialized: the memory, instruction pointer, relative base and pending inputs
+impl<T> Serialize for IntcodeVM<T>
+where
+    T: Integer + Clone + ToPrimitive + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        VMStateRef {
+            memory: self.memory(),
+            instruction_pointer: self.instruction_pointer(),
+            relative_base: self.relative_base(),
+            inputs: self.pending_inputs().collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// The deserialized memory is also used as the initial memory of the VM
+impl<'de, T> Deserialize<'de> for IntcodeVM<T>
+where
+    T: Integer + Clone + ToPrimitive + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = VMState::deserialize(deserializer)?;
+        let mut vm = IntcodeVM::new(state.memory);
+        vm.set_instruction_pointer(state.instruction_pointer);
+        vm.set_relative_base(state.relative_base);
+        vm.extend_input(state.inputs);
+        Ok(vm)
+    }
+}
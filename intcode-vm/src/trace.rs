@@ -0,0 +1,253 @@
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+#[cfg(feature = "std")]
+use num::{FromPrimitive, ToPrimitive};
+
+use crate::VMResult;
+
+/// Magic bytes at the start of a binary trace (the last byte being the format version)
+#[cfg(feature = "std")]
+const MAGIC: [u8; 4] = *b"ICT\x01";
+
+/// Number of times each opcode was executed by a [VM](crate::IntcodeVM)
+/// (see [`IntcodeVM::opcode_counts()`](crate::IntcodeVM::opcode_counts))
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct OpcodeCounts([u64; 10]);
+
+impl OpcodeCounts {
+    /// Every opcode, in the order used by [`OpcodeCounts::iter()`](OpcodeCounts::iter)
+    pub const OPCODES: [u16; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 99];
+
+    /// Number of times `opcode` (without its argument modes) was executed, 0 if it is not a valid opcode
+    #[inline]
+    pub fn get(&self, opcode: u16) -> u64 {
+        Self::index(opcode).map_or(0, |index| self.0[index])
+    }
+
+    /// `(opcode, count)` of every opcode
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (u16, u64)> + '_ {
+        Self::OPCODES.into_iter().zip(self.0)
+    }
+
+    /// Total number of instructions counted
+    #[inline]
+    pub fn total(&self) -> u64 {
+        self.0.iter().sum()
+    }
+
+    #[inline]
+    pub(crate) fn record(&mut self, opcode: u16) {
+        if let Some(index) = Self::index(opcode) {
+            self.0[index] += 1;
+        }
+    }
+
+    #[inline]
+    const fn index(opcode: u16) -> Option<usize> {
+        match opcode {
+            1..=9 => Some(opcode as usize - 1),
+            99 => Some(9),
+            _ => None,
+        }
+    }
+}
+
+/// Instruction about to be executed, passed to the hook set with
+/// [`IntcodeVM::set_trace_hook()`](crate::IntcodeVM::set_trace_hook)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraceEvent<T> {
+    /// Address of the instruction
+    pub ip: usize,
+    /// Operation of the instruction (the 2 last digits of `word`)
+    pub opcode: u16,
+    /// Mode of the 3 arguments (0 positional, 1 immediate, 2 relative) even if the instruction has less
+    pub modes: [u8; 3],
+    /// Value of the instruction in memory
+    pub word: T,
+}
+
+/// Record of a [VM's](crate::vm::IntcodeVM) execution
+///
+/// Contains the address of every executed instruction (in order) and every output produced
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Trace<T> {
+    instruction_pointers: Vec<usize>,
+    outputs: Vec<T>,
+}
+
+impl<T> Default for Trace<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            instruction_pointers: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+}
+
+impl<T> Trace<T> {
+    /// Addresses of the executed instructions, in execution order
+    #[inline]
+    pub fn instruction_pointers(&self) -> &[usize] {
+        &self.instruction_pointers
+    }
+
+    /// Values outputted by the program, in output order
+    #[inline]
+    pub fn outputs(&self) -> &[T] {
+        &self.outputs
+    }
+
+    #[inline]
+    pub(crate) fn record(&mut self, instruction_ptr: usize, result: Option<&VMResult<T>>)
+    where
+        T: Clone,
+    {
+        self.instruction_pointers.push(instruction_ptr);
+        if let Some(VMResult::Output(out)) = result {
+            self.outputs.push(out.clone());
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Trace<T>
+where
+    T: ToPrimitive,
+{
+    /// Writes the trace in a compact binary format, readable with [`Trace::read_binary()`](Trace::read_binary)
+    ///
+    /// Instruction pointers are stored as the (variable length encoded) difference with the previous one,
+    /// most of them fit in a single byte.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`InvalidData`](io::ErrorKind::InvalidData)
+    /// if an output does not fit in an [`i64`], or any error returned by `writer`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{trace::Trace, IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([104, -5, 104, 1125899906842624, 99]);
+    /// vm.enable_trace();
+    /// while vm.run().unwrap() != VMResult::Halted {}
+    ///
+    /// let mut bytes = Vec::new();
+    /// vm.write_trace_binary(&mut bytes).unwrap();
+    ///
+    /// let trace: Trace<i64> = Trace::read_binary(bytes.as_slice()).unwrap();
+    /// assert_eq!(Some(&trace), vm.trace());
+    /// ```
+    pub fn write_binary<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&MAGIC)?;
+
+        write_varint(&mut writer, self.instruction_pointers.len() as u64)?;
+        let mut previous = 0usize;
+        for &ptr in &self.instruction_pointers {
+            let delta = ptr.wrapping_sub(previous) as i64;
+            write_varint(&mut writer, zigzag_encode(delta))?;
+            previous = ptr;
+        }
+
+        write_varint(&mut writer, self.outputs.len() as u64)?;
+        for out in &self.outputs {
+            let value = out.to_i64().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "output does not fit in an i64")
+            })?;
+            write_varint(&mut writer, zigzag_encode(value))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Trace<T>
+where
+    T: FromPrimitive,
+{
+    /// Reads a trace written by [`Trace::write_binary()`](Trace::write_binary)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`InvalidData`](io::ErrorKind::InvalidData) if the data is not a trace
+    /// (or an output cannot be represented by `T`), or any error returned by `reader`
+    pub fn read_binary<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_data("not an intcode trace"));
+        }
+
+        let len = read_varint(&mut reader)?;
+        let mut instruction_pointers = Vec::new();
+        let mut previous = 0usize;
+        for _ in 0..len {
+            let delta = zigzag_decode(read_varint(&mut reader)?);
+            previous = previous.wrapping_add(delta as usize);
+            instruction_pointers.push(previous);
+        }
+
+        let len = read_varint(&mut reader)?;
+        let mut outputs = Vec::new();
+        for _ in 0..len {
+            let value = zigzag_decode(read_varint(&mut reader)?);
+            outputs.push(T::from_i64(value).ok_or_else(|| invalid_data("output overflows T"))?);
+        }
+
+        Ok(Self {
+            instruction_pointers,
+            outputs,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+#[inline]
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(feature = "std")]
+#[inline]
+const fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+#[cfg(feature = "std")]
+#[inline]
+const fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+#[cfg(feature = "std")]
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+#[cfg(feature = "std")]
+fn read_varint<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        value |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(invalid_data("variable length integer is too long"))
+}
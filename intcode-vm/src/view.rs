@@ -0,0 +1,185 @@
+use alloc::collections::BTreeMap;
+
+use num::{Integer, ToPrimitive};
+
+use crate::{
+    error,
+    memory::{sealed, Memory, MemoryRead, MemoryStore},
+    vm::HaltBehavior,
+    IntcodeVM, VMResult,
+};
+
+/// A VM executing a program it borrows rather than owns
+///
+/// Every write goes to an overlay specific to the view, the borrowed [Memory] is never modified:
+/// any number of views can run the same program at the same time without cloning it,
+/// each of them only storing the cells it wrote to.
+///
+/// The program is executed exactly like an [IntcodeVM] would,
+/// including its [halt behavior](VmView::set_halt_behavior), [breakpoints](VmView::add_breakpoint)
+/// and [memory limit](VmView::set_max_memory).
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::{memory::Memory, view::VmView, VMResult};
+/// // outputs its input times 3
+/// let program = Memory::from([3, 9, 1002, 9, 3, 9, 4, 9, 99, 0]);
+///
+/// let mut views: Vec<_> = (0..10).map(|_| VmView::new(&program)).collect();
+/// for (input, view) in views.iter_mut().enumerate() {
+///     assert_eq!(view.run().unwrap(), VMResult::WaitingForInput);
+///     view.set_next_input(input as i32);
+///     assert_eq!(view.run().unwrap(), VMResult::Output(input as i32 * 3));
+/// }
+///
+/// // the program itself is untouched
+/// assert_eq!(program[9], 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct VmView<'a, T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    vm: IntcodeVM<T, Overlay<'a, T>>,
+}
+
+impl<'a, T> VmView<'a, T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    /// Creates a new view executing the program in `base`
+    #[inline]
+    pub fn new(base: &'a Memory<T>) -> Self {
+        Self {
+            vm: IntcodeVM::with_memory(Overlay {
+                base,
+                cells: BTreeMap::new(),
+                len: base.len(),
+            }),
+        }
+    }
+
+    /// Executes the program, just like [`IntcodeVM::run()`](crate::IntcodeVM::run)
+    #[inline]
+    pub fn run(&mut self) -> error::Result<VMResult<T>, T> {
+        self.vm.run()
+    }
+
+    /// Executes a single instruction, just like [`IntcodeVM::step()`](crate::IntcodeVM::step)
+    #[inline]
+    pub fn step(&mut self) -> error::Result<VMResult<T>, T> {
+        self.vm.step()
+    }
+
+    /// Value of the cell at `address`, as seen by this view
+    #[inline]
+    pub fn get(&self, address: usize) -> &T {
+        self.vm.memory().get(address)
+    }
+
+    /// Cells written by this view
+    #[inline]
+    pub fn overlay(&self) -> &BTreeMap<usize, T> {
+        &self.vm.memory().cells
+    }
+
+    /// Creates a [Memory] with the content of the borrowed program, as modified by this view
+    pub fn to_memory(&self) -> Memory<T> {
+        let overlay = self.vm.memory();
+        let mut memory = overlay.base.clone();
+        for (&address, value) in &overlay.cells {
+            memory.set(address, value.clone());
+        }
+
+        memory
+    }
+
+    #[inline]
+    pub const fn instruction_pointer(&self) -> usize {
+        self.vm.instruction_pointer()
+    }
+
+    #[inline]
+    pub const fn get_next_input(&self) -> &Option<T> {
+        self.vm.get_next_input()
+    }
+
+    #[inline]
+    pub fn set_next_input(&mut self, next_input: T) -> Option<T> {
+        self.vm.set_next_input(next_input)
+    }
+
+    /// Adds `value` at the back of the input queue
+    /// (see [`IntcodeVM::push_input()`](crate::IntcodeVM::push_input))
+    #[inline]
+    pub fn push_input(&mut self, value: T) {
+        self.vm.push_input(value);
+    }
+
+    /// See [`IntcodeVM::set_halt_behavior()`](crate::IntcodeVM::set_halt_behavior)
+    #[inline]
+    pub fn set_halt_behavior(&mut self, behavior: HaltBehavior) {
+        self.vm.set_halt_behavior(behavior);
+    }
+
+    /// See [`IntcodeVM::add_breakpoint()`](crate::IntcodeVM::add_breakpoint)
+    #[inline]
+    pub fn add_breakpoint(&mut self, address: usize) {
+        self.vm.add_breakpoint(address);
+    }
+
+    /// See [`IntcodeVM::remove_breakpoint()`](crate::IntcodeVM::remove_breakpoint)
+    #[inline]
+    pub fn remove_breakpoint(&mut self, address: usize) {
+        self.vm.remove_breakpoint(address);
+    }
+
+    /// See [`IntcodeVM::set_max_memory()`](crate::IntcodeVM::set_max_memory)
+    #[inline]
+    pub fn set_max_memory(&mut self, max: usize) {
+        self.vm.set_max_memory(max);
+    }
+}
+
+/// Cells written by a [VmView], read on top of the borrowed program
+#[derive(Debug, Clone)]
+pub(crate) struct Overlay<'a, T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    base: &'a Memory<T>,
+    cells: BTreeMap<usize, T>,
+    /// Length of the memory as seen through the overlay
+    len: usize,
+}
+
+impl<'a, T> MemoryRead<T> for Overlay<'a, T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    #[inline]
+    fn get(&self, address: usize) -> &T {
+        self.cells
+            .get(&address)
+            .unwrap_or_else(|| self.base.get(address))
+    }
+}
+
+impl<'a, T> sealed::Store<T> for Overlay<'a, T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    #[inline]
+    fn set(&mut self, address: usize, value: T) {
+        self.len = self.len.max(address + 1);
+        self.cells.insert(address, value);
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, T> MemoryStore<T> for Overlay<'a, T> where T: Integer + Clone + ToPrimitive {}
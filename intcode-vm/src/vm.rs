@@ -1,14 +1,36 @@
-use std::str::FromStr;
+use core::{
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    str::FromStr,
+};
+#[cfg(feature = "std")]
+use std::{io, path::Path};
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    sync::Arc,
+    vec::Vec,
+};
 
-use num::{Integer, ToPrimitive};
+use num::{CheckedAdd, CheckedMul, Integer, ToPrimitive};
 
+#[cfg(feature = "std")]
+use crate::error::FromFileError;
 use crate::{
-    error::{self, VMError},
-    memory::Memory,
+    disasm::DecodedInstruction,
+    error::{self, ParseError, VMError},
+    hook::Hook,
+    memory::{Memory, MemoryStore},
+    trace::{OpcodeCounts, Trace, TraceEvent},
 };
 
 /// A [VM](IntcodeVM) will return a variant of this enum when it encounters some instructions
+///
+/// More variants may be added as the VM gains features (that are not enabled by default)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum VMResult<T> {
     /// Encountered opcode: 99
     ///
@@ -24,10 +46,224 @@ pub enum VMResult<T> {
     /// You can call [`vm.run()`](IntcodeVM::run) again without changing anything.
     /// The associated value of this variant is the output generated by the instruction.
     Output(T),
+    /// Encountered opcode: 99, with the halt behavior set to [`HaltBehavior::Pause`]
+    ///
+    /// The instruction pointer stays on the halt instruction,
+    /// calling [`vm.run()`](IntcodeVM::run) again would pause immediatly again
+    Paused,
+    /// Executed an instruction that neither halts, outputs nor waits for an input
+    ///
+    /// Only returned by [`vm.step()`](IntcodeVM::step)
+    Continue,
+    /// Reached a breakpoint (see [`IntcodeVM::add_breakpoint()`](IntcodeVM::add_breakpoint)),
+    /// the associated value being its address
+    ///
+    /// The instruction at the breakpoint is not executed yet,
+    /// calling [`vm.run()`](IntcodeVM::run) again resumes the execution from it.
+    BreakpointHit(usize),
+    /// An instruction wrote to a watched cell (see [`IntcodeVM::add_watchpoint()`](IntcodeVM::add_watchpoint))
+    ///
+    /// The instruction was executed, calling [`vm.run()`](IntcodeVM::run) again resumes
+    /// the execution from the next one.
+    WatchpointHit {
+        /// Address of the cell
+        address: usize,
+        /// Value of the cell before the write
+        old: T,
+        /// Value written to the cell
+        new: T,
+    },
+    /// The fuel of the VM is exhausted (see [`IntcodeVM::set_fuel()`](IntcodeVM::set_fuel))
+    ///
+    /// The instruction at the instruction pointer is not executed yet,
+    /// calling [`vm.run()`](IntcodeVM::run) again after [refueling](IntcodeVM::add_fuel) resumes the execution from it.
+    OutOfFuel,
+}
+
+impl<T> VMResult<T> {
+    /// Maps the values carried by the result (the output, or the values of a watched cell) with `f`,
+    /// leaving the other variants as they are
+    ///
+    /// `f` is an [`FnMut`] rather than an [`FnOnce`] since [`VMResult::WatchpointHit`]
+    /// carries two values, `f` is called on the old value first, then on the new one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::VMResult;
+    /// assert_eq!(VMResult::Output(65).map(|v| v as u8 as char), VMResult::Output('A'));
+    /// assert_eq!(VMResult::Halted.map(|v: i64| v as u8 as char), VMResult::Halted);
+    /// assert_eq!(
+    ///     VMResult::WatchpointHit { address: 3, old: 1, new: 2 }.map(|v| v * 10),
+    ///     VMResult::WatchpointHit { address: 3, old: 10, new: 20 }
+    /// );
+    /// ```
+    pub fn map<U, F: FnMut(T) -> U>(self, mut f: F) -> VMResult<U> {
+        match self {
+            Self::Halted => VMResult::Halted,
+            Self::WaitingForInput => VMResult::WaitingForInput,
+            Self::Output(out) => VMResult::Output(f(out)),
+            Self::Paused => VMResult::Paused,
+            Self::Continue => VMResult::Continue,
+            Self::BreakpointHit(address) => VMResult::BreakpointHit(address),
+            Self::OutOfFuel => VMResult::OutOfFuel,
+            Self::WatchpointHit { address, old, new } => VMResult::WatchpointHit {
+                address,
+                old: f(old),
+                new: f(new),
+            },
+        }
+    }
+}
+
+/// What a [VM](IntcodeVM) does when it encounters opcode 99
+/// (see [`IntcodeVM::set_halt_behavior()`](IntcodeVM::set_halt_behavior))
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HaltBehavior {
+    /// Return [`VMResult::Halted`]
+    #[default]
+    Terminate,
+    /// Return [`VMResult::Paused`]
+    Pause,
+}
+
+/// What a [VM](IntcodeVM) does when it encounters opcode 03 while its input queue is empty
+/// (see [`IntcodeVM::set_input_on_empty()`](IntcodeVM::set_input_on_empty))
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EmptyInputBehavior<T> {
+    /// Return [`VMResult::WaitingForInput`]
+    #[default]
+    WaitForInput,
+    /// Use the associated value as input and continue the execution
+    Yield(T),
+}
+
+/// Overflow-checked addition and multiplication of `T`
+/// (see [`IntcodeVM::set_checked_arithmetic()`](IntcodeVM::set_checked_arithmetic))
+#[derive(Debug, Clone, Copy)]
+struct CheckedOps<T> {
+    add: fn(&T, &T) -> Option<T>,
+    mul: fn(&T, &T) -> Option<T>,
+}
+
+/// Recent instructions executed by a [VM](IntcodeVM), to undo them
+/// (see [`IntcodeVM::enable_history()`](IntcodeVM::enable_history))
+#[derive(Debug, Clone)]
+struct History<T> {
+    depth: usize,
+    /// Oldest instruction first
+    entries: VecDeque<HistoryEntry<T>>,
+    /// Cell written by the instruction being executed, along with its previous value
+    current_write: Option<(usize, T)>,
+}
+
+/// State of a [VM](IntcodeVM) before an instruction, stored as the changes made by the instruction
+#[derive(Debug, Clone)]
+struct HistoryEntry<T> {
+    instruction_ptr: usize,
+    relative_base_ptr: T,
+    memory_len: usize,
+    /// Cell written by the instruction, along with its previous value
+    write: Option<(usize, T)>,
+    /// Input taken from the input queue (or the input iterator) by the instruction, put back in the queue when undoing it
+    input: Option<T>,
 }
 
+/// Value written in the [log] messages, as a decimal integer (`<big>` if it does not fit in an [`i128`])
+///
+/// `T` is not required to implement [`Display`](core::fmt::Display), the value goes through [ToPrimitive].
+#[cfg(feature = "log")]
+struct LogValue<'t, T>(&'t T);
+
+#[cfg(feature = "log")]
+impl<'t, T: ToPrimitive> core::fmt::Display for LogValue<'t, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.0.to_i128() {
+            Some(value) => write!(f, "{value}"),
+            None => f.write_str("<big>"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IntcodeVM<T, M = Memory<T>>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    memory: M,
+    initial_memory: Arc<M>,
+    instruction_ptr: usize,
+    relative_base_ptr: T,
+    /// Front of the input queue, the rest being in `input_queue` (which is empty when this is [`None`])
+    next_input_value: Option<T>,
+    input_queue: VecDeque<T>,
+    buffer_output: bool,
+    /// Cell the output instructions write to instead of outputting
+    output_address: Option<usize>,
+    output_buffer: Vec<T>,
+    trace: Option<Trace<T>>,
+    profile_accesses: bool,
+    access_profile: BTreeMap<usize, (u64, u64)>,
+    profile_opcodes: bool,
+    opcode_counts: OpcodeCounts,
+    instruction_starts: Option<BTreeSet<usize>>,
+    halt_behavior: HaltBehavior,
+    input_on_empty: EmptyInputBehavior<T>,
+    input_log: Option<Vec<(usize, T)>>,
+    peak_memory_len: Option<usize>,
+    instructions_executed: u64,
+    max_memory: Option<usize>,
+    strict_memory: bool,
+    code_limit: Option<usize>,
+    instruction_limit: Option<u64>,
+    fuel: Option<u64>,
+    checked_arithmetic: Option<CheckedOps<T>>,
+    trace_hook: Hook<dyn FnMut(TraceEvent<T>) + Send>,
+    output_hook: Hook<dyn FnMut(T) + Send>,
+    input_iter: Hook<dyn Iterator<Item = T> + Send>,
+    breakpoints: BTreeSet<usize>,
+    /// Breakpoint the execution stopped at, not triggered again when resuming from it
+    resumed_breakpoint: Option<usize>,
+    watchpoints: BTreeSet<usize>,
+    /// Write to a watched cell made by the instruction being executed, as `(address, old, new)`
+    watchpoint_hit: Option<(usize, T, T)>,
+    history: Option<History<T>>,
+}
+
+/// State of a [VM](IntcodeVM) waiting for an input, from which the execution can be resumed
+/// any number of times (see [`IntcodeVM::run_to_input_checkpoint()`](IntcodeVM::run_to_input_checkpoint))
+#[derive(Debug, Clone)]
+pub struct VMCheckpoint<T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    vm: IntcodeVM<T>,
+    outputs: Vec<T>,
+}
+
+impl<T> VMCheckpoint<T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    /// Values outputted by the program before reaching the checkpoint
+    #[inline]
+    pub fn outputs(&self) -> &[T] {
+        &self.outputs
+    }
+
+    /// Creates a VM resuming the execution from the checkpoint, with `input` as the requested input
+    #[inline]
+    pub fn resume_with(&self, input: T) -> IntcodeVM<T> {
+        let mut vm = self.vm.clone();
+        vm.set_next_input(input);
+        vm
+    }
+}
+
+/// Saved execution state of a [VM](IntcodeVM): its memory, instruction pointer, relative base and input queue
+/// (see [`IntcodeVM::snapshot()`](IntcodeVM::snapshot))
 #[derive(Debug, Clone)]
-pub struct IntcodeVM<T>
+pub struct VMSnapshot<T>
 where
     T: Integer + Clone + ToPrimitive,
 {
@@ -35,14 +271,54 @@ where
     instruction_ptr: usize,
     relative_base_ptr: T,
     next_input_value: Option<T>,
+    input_queue: VecDeque<T>,
+}
+
+/// [Iterator] over the outputs of a [VM](IntcodeVM) (see [`IntcodeVM::outputs()`](IntcodeVM::outputs))
+#[derive(Debug)]
+pub struct Outputs<'vm, T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    vm: &'vm mut IntcodeVM<T>,
+    done: bool,
+}
+
+impl<'vm, T> Iterator for Outputs<'vm, T>
+where
+    T: Integer + Clone + ToPrimitive,
+{
+    type Item = error::Result<T, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.vm.run() {
+            Ok(VMResult::Output(out)) => Some(Ok(out)),
+            Ok(_) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
 }
 
+impl<'vm, T> FusedIterator for Outputs<'vm, T> where T: Integer + Clone + ToPrimitive {}
+
 impl<T> IntcodeVM<T>
 where
     T: Integer + Clone + ToPrimitive,
 {
     /// Creates a new VM from the given [`memory`](Memory)
     ///
+    /// A copy of the initial memory is kept (and shared between clones of the VM)
+    ///
     /// # Example
     ///
     /// ```
@@ -51,186 +327,2380 @@ where
     /// ```
     #[inline]
     pub fn new<I: Into<Memory<T>>>(memory: I) -> Self {
-        Self {
-            memory: memory.into(),
-            instruction_ptr: 0,
-            relative_base_ptr: T::zero(),
-            next_input_value: None,
+        Self::with_memory(memory.into())
+    }
+
+    /// Sets the cell at `address` to `value` before running the program
+    ///
+    /// The patched program becomes the one the VM was created with
+    /// (the one [`IntcodeVM::reset()`](IntcodeVM::reset) restores). As such, the patched cells are not
+    /// reported by [`IntcodeVM::diff_from_initial()`](IntcodeVM::diff_from_initial), and patching a cell
+    /// past the end of the program grows [`IntcodeVM::program_len()`](IntcodeVM::program_len) to include it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([1, 0, 0, 0, 99]).with_patch(1, 4);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// assert_eq!(vm.diff_from_initial(), [(0, 100)]);
+    /// assert_eq!(vm.into_memory().get(0), &100);
+    ///
+    /// assert_eq!(IntcodeVM::new([99]).with_patch(100, 1).program_len(), 101);
+    /// ```
+    #[inline]
+    pub fn with_patch(self, address: usize, value: T) -> Self {
+        self.with_patches([(address, value)])
+    }
+
+    /// Sets the cells at the given addresses to the associated values before running the program
+    /// (as per [`IntcodeVM::with_patch()`](IntcodeVM::with_patch), the patched program becoming
+    /// the one the VM was created with)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([1, 0, 0, 0, 99]).with_patches([(1, 4), (2, 4)]);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// assert_eq!(vm.into_memory().get(0), &198);
+    /// ```
+    pub fn with_patches<I: IntoIterator<Item = (usize, T)>>(mut self, patches: I) -> Self {
+        let initial_memory = Arc::make_mut(&mut self.initial_memory);
+        for (address, value) in patches {
+            initial_memory.set(address, value.clone());
+            self.memory.set(address, value);
+        }
+
+        self
+    }
+
+    /// Runs the program, yielding its outputs
+    ///
+    /// The iteration stops when the program halts or waits for an input while the input queue is empty,
+    /// or after yielding an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::IntcodeVM;
+    /// let mut vm = IntcodeVM::new([104, 1, 104, 2, 3, 0, 104, 3, 99]);
+    /// assert_eq!(vm.outputs().collect::<Result<Vec<_>, _>>().unwrap(), [1, 2]);
+    ///
+    /// vm.push_input(0);
+    /// assert_eq!(vm.outputs().collect::<Result<Vec<_>, _>>().unwrap(), [3]);
+    /// ```
+    #[inline]
+    pub fn outputs(&mut self) -> Outputs<'_, T> {
+        Outputs {
+            vm: self,
+            done: false,
+        }
+    }
+
+    /// Runs the program until it waits for an input, then returns a [VMCheckpoint] of that state
+    /// (along with the values outputted until then)
+    ///
+    /// Trying many inputs from the checkpoint avoids running the part of the program
+    /// that does not depend on the input again and again.
+    /// Returns [`None`] if the program halts before asking for an input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// // outputs 7, then outputs its input times 2
+    /// let mut vm = IntcodeVM::new([104, 7, 3, 11, 1002, 11, 2, 11, 4, 11, 99, 0]);
+    /// let checkpoint = vm.run_to_input_checkpoint().unwrap().unwrap();
+    /// assert_eq!(checkpoint.outputs(), &[7]);
+    ///
+    /// for input in 0..10 {
+    ///     let mut vm = checkpoint.resume_with(input);
+    ///     assert_eq!(vm.run().unwrap(), VMResult::Output(input * 2));
+    /// }
+    /// ```
+    pub fn run_to_input_checkpoint(&mut self) -> error::Result<Option<VMCheckpoint<T>>, T> {
+        let mut outputs = Vec::new();
+        loop {
+            match self.run()? {
+                VMResult::OutOfFuel => return Err(VMError::OutOfFuel),
+                VMResult::Output(out) => outputs.push(out),
+                VMResult::WaitingForInput => {
+                    return Ok(Some(VMCheckpoint {
+                        vm: self.clone(),
+                        outputs,
+                    }))
+                }
+                VMResult::BreakpointHit(_) | VMResult::WatchpointHit { .. } => (),
+                _ => return Ok(None),
+            }
+        }
+    }
+
+    /// Builds the smallest memory image reproducing the error this VM runs into,
+    /// without modifying this VM (the execution is done on a clone)
+    ///
+    /// The returned memory only contains the cells (instructions and data) that were read or written
+    /// until the error occurred, every other cell is set to the [fill value](Memory::fill) of the memory
+    /// (0 by default), which the image keeps. Any provided input is used just like
+    /// [`IntcodeVM::run()`](IntcodeVM::run) would, outputs are ignored.
+    /// The error is reproduced by running the image from the current instruction pointer
+    /// and relative base (both are 0 for a VM that was not run yet).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VMError::NoErrorToReproduce`] if the program stops without failing: when it halts, waits for an input,
+    /// [pauses](HaltBehavior::Pause), runs out of [fuel](IntcodeVM::set_fuel)
+    /// or exceeds the [instruction limit](IntcodeVM::set_instruction_limit) of this VM.
+    /// A program looping forever without failing makes this method never return if neither of the last two is set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::IntcodeVM;
+    /// // jumps over some unused data to the invalid opcode 15, at address 6
+    /// let vm = IntcodeVM::new([1105, 1, 6, 42, 42, 42, 15, 42]);
+    /// let repro = vm.minimal_repro().unwrap();
+    /// assert!(repro.memory_starts_with(&[1105, 1, 6, 0, 0, 0, 15]));
+    /// assert!(IntcodeVM::new(repro).run().is_err());
+    /// ```
+    pub fn minimal_repro(&self) -> error::Result<Memory<T>, T> {
+        let mut vm = self.clone();
+        vm.trace = None;
+        vm.enable_access_profiling();
+
+        let mut touched = BTreeSet::new();
+        loop {
+            let instruction_ptr = vm.instruction_ptr;
+            match instr::Instruction::from_current_instr_ptr(&vm) {
+                Ok(instruction) => {
+                    touched
+                        .extend(instruction_ptr..instruction_ptr + instruction.instruction_width());
+                    // the failing instruction's operands are not profiled, take all of them
+                    touched.extend(instruction.operand_addresses(&vm));
+                }
+                Err(_) => {
+                    touched.insert(instruction_ptr);
+                }
+            }
+
+            match vm.execute_instruction() {
+                Ok(None | Some(VMResult::Output(_))) => (),
+                Ok(Some(_)) | Err(VMError::InstructionLimitExceeded { .. }) => {
+                    return Err(VMError::NoErrorToReproduce)
+                }
+                Err(_) => break,
+            }
+        }
+
+        touched.extend(vm.access_profile.keys().copied());
+        let len = touched.last().map_or(0, |&last| last + 1);
+        let fill = self.memory.fill();
+        let image = (0..len)
+            .map(|address| {
+                if touched.contains(&address) {
+                    self.memory.get(address).clone()
+                } else {
+                    fill.clone()
+                }
+            })
+            .collect();
+        Ok(Memory::with_fill(image, fill.clone()))
+    }
+
+    /// Returns the internal [Memory] of the VM
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::IntcodeVM;
+    /// let vm = IntcodeVM::from([1, 0, 0, 3, 99]);
+    /// let memory = vm.into_memory();
+    ///
+    /// assert!(memory.memory_starts_with([1, 0, 0, 3, 99].iter()));
+    /// ```
+    #[inline]
+    pub fn into_memory(self) -> Memory<T> {
+        self.memory
+    }
+
+    /// Copy of the current [Memory] of the VM, which keeps running with its own
+    ///
+    /// Unlike cloning the VM (or taking a [snapshot](IntcodeVM::snapshot)), only the memory is copied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// // adds 1 to the cell at address 7, then outputs it
+    /// let mut vm = IntcodeVM::new([1001, 7, 1, 7, 4, 7, 99, 41]);
+    /// let before = vm.clone_memory();
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(42));
+    /// assert_eq!(before.diff(vm.memory()), [(7, 41, 42)]);
+    /// ```
+    #[inline]
+    pub fn clone_memory(&self) -> Memory<T> {
+        self.memory.clone()
+    }
+
+    /// Saves the execution state of the VM, to be restored with [`IntcodeVM::restore()`](IntcodeVM::restore)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// // outputs 1 if the input is 8, 0 otherwise
+    /// let mut vm = IntcodeVM::new([3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8]);
+    /// let snapshot = vm.snapshot();
+    ///
+    /// let input = (0..)
+    ///     .find(|&input| {
+    ///         vm.restore(snapshot.clone());
+    ///         vm.push_input(input);
+    ///         vm.run().unwrap() == VMResult::Output(1)
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(input, 8);
+    /// ```
+    pub fn snapshot(&self) -> VMSnapshot<T> {
+        VMSnapshot {
+            memory: self.memory.clone(),
+            instruction_ptr: self.instruction_ptr,
+            relative_base_ptr: self.relative_base_ptr.clone(),
+            next_input_value: self.next_input_value.clone(),
+            input_queue: self.input_queue.clone(),
         }
     }
 
-    /// Executes the intcode program in the memory of the VM
-    ///
-    /// When a halt instruction is encountered, returns [`Ok(VMResult::Halted)`](VMResult::Halted)
+    /// Restores the execution state saved in `snapshot` (see [`IntcodeVM::snapshot()`](IntcodeVM::snapshot))
+    #[inline]
+    pub fn restore(&mut self, snapshot: VMSnapshot<T>) {
+        self.memory = snapshot.memory;
+        self.instruction_ptr = snapshot.instruction_ptr;
+        self.relative_base_ptr = snapshot.relative_base_ptr;
+        self.next_input_value = snapshot.next_input_value;
+        self.input_queue = snapshot.input_queue;
+        self.resumed_breakpoint = None;
+        if let Some(history) = &mut self.history {
+            history.entries.clear();
+        }
+    }
+
+    /// Starts keeping the changes made by the last `depth` executed instructions,
+    /// so that they can be undone with [`IntcodeVM::step_back()`](IntcodeVM::step_back)
+    ///
+    /// Only the changes are stored (the cell written and the input read by each instruction),
+    /// not copies of the whole memory.
+    #[inline]
+    pub fn enable_history(&mut self, depth: usize) {
+        self.history = Some(History {
+            depth,
+            entries: VecDeque::with_capacity(depth),
+            current_write: None,
+        });
+    }
+
+    /// Undoes the last executed instruction kept by the [history](IntcodeVM::enable_history),
+    /// restoring the memory, instruction pointer, relative base and input queue from before it
+    ///
+    /// Returns `false` (without changing anything) if the history is empty or not enabled.
+    /// The outputs of the undone instructions are not taken back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// // outputs its input times 3
+    /// let mut vm = IntcodeVM::new([3, 9, 1002, 9, 3, 9, 4, 9, 99, 0]);
+    /// vm.enable_history(2);
+    /// vm.push_input(5);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(15));
+    ///
+    /// assert!(vm.step_back()); // the output
+    /// assert!(vm.step_back()); // the multiplication
+    /// assert_eq!(vm.instruction_pointer(), 2);
+    /// assert_eq!(vm.diff_from_initial(), [(9, 5)]);
+    ///
+    /// // the input instruction is not kept anymore
+    /// assert!(!vm.step_back());
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(15));
+    /// ```
+    pub fn step_back(&mut self) -> bool {
+        let Some(entry) = self
+            .history
+            .as_mut()
+            .and_then(|history| history.entries.pop_back())
+        else {
+            return false;
+        };
+
+        if let Some((address, value)) = entry.write {
+            self.memory.set(address, value);
+        }
+        if self.memory.len() != entry.memory_len {
+            self.memory.resize_to(entry.memory_len);
+        }
+        self.instruction_ptr = entry.instruction_ptr;
+        self.relative_base_ptr = entry.relative_base_ptr;
+        if let Some(input) = entry.input {
+            if let Some(next) = self.next_input_value.replace(input) {
+                self.input_queue.push_front(next);
+            }
+        }
+        self.resumed_breakpoint = None;
+
+        true
+    }
+
+    /// Cells of the memory whose value differs from the program the VM was created with,
+    /// as `(address, value)` pairs in increasing address order
+    ///
+    /// The VM can be rebuilt from it with [`IntcodeVM::from_initial_and_diff()`](IntcodeVM::from_initial_and_diff)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([1101, 2, 3, 10, 1101, 4, 0, 2, 99]);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    ///
+    /// assert_eq!(vm.diff_from_initial(), [(2, 4), (10, 5)]);
+    /// ```
+    pub fn diff_from_initial(&self) -> Vec<(usize, T)> {
+        self.initial_memory
+            .diff(&self.memory)
+            .into_iter()
+            .map(|(address, _, value)| (address, value))
+            .collect()
+    }
+
+    /// Creates a new VM from the `initial` program with the cells in `diff` overwritten
+    /// (as returned by [`IntcodeVM::diff_from_initial()`](IntcodeVM::diff_from_initial))
+    ///
+    /// Only the memory is restored: the instruction pointer and relative base start at 0
+    /// and `initial` is kept as the initial memory of the VM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{memory::Memory, IntcodeVM};
+    /// let program = Memory::from([1101, 2, 3, 10, 99]);
+    /// let vm = IntcodeVM::from_initial_and_diff(program, vec![(0, 1102), (10, 6)]);
+    ///
+    /// assert_eq!(vm.diff_from_initial(), [(0, 1102), (10, 6)]);
+    /// assert!(vm.into_memory().memory_starts_with(&[1102, 2, 3, 10, 99, 0, 0, 0, 0, 0, 6]));
+    /// ```
+    pub fn from_initial_and_diff(initial: Memory<T>, diff: Vec<(usize, T)>) -> Self {
+        let mut vm = Self::new(initial);
+        for (address, value) in diff {
+            vm.memory.set(address, value);
+        }
+
+        vm
+    }
+
+    /// Addresses of the memory cells read or written through positional arguments by the instructions
+    /// statically reachable from the instruction pointer
+    ///
+    /// The execution flow is followed as far as it can be without running the program:
+    /// instructions only reachable through jumps with a computed target are not scanned,
+    /// and self-modifying code is not taken into account.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::IntcodeVM;
+    /// let vm = IntcodeVM::new([
+    ///     1, 9, 10, 11, // adds 9 and 10 into 11
+    ///     1105, 1, 9,   // jumps over the next instruction
+    ///     4, 12,        // outputs 12, never executed
+    ///     99,
+    ///     30, 40, 50,
+    /// ]);
+    ///
+    /// // 9 is the halt instruction, but is also read by the first instruction
+    /// assert_eq!(vm.referenced_data_addresses().into_iter().collect::<Vec<_>>(), [9, 10, 11]);
+    /// ```
+    pub fn referenced_data_addresses(&self) -> BTreeSet<usize> {
+        instr::reachable_instructions(&self.memory, self.instruction_ptr)
+            .values()
+            .flatten()
+            .flat_map(instr::Instruction::positional_operands)
+            .collect()
+    }
+
+    /// Makes jumps fail with [`VMError::MisalignedJump`] when they land on an address
+    /// that is not the start of an instruction
+    ///
+    /// The instruction starts are found by decoding the current memory linearly from address 0,
+    /// cells that cannot be decoded are treated as data.
+    /// Jumping in the middle of an instruction is valid intcode (so this check is not enabled by default),
+    /// but is often a mistake in hand-written programs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::VMError, IntcodeVM, VMResult};
+    /// // jumps to 4 (the start of the halt instruction)
+    /// let mut vm = IntcodeVM::new([1105, 1, 4, 99, 99]);
+    /// vm.enable_alignment_check();
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    ///
+    /// // jumps to 2, in the middle of the jump instruction
+    /// let mut vm = IntcodeVM::new([1105, 1, 2, 99]);
+    /// vm.enable_alignment_check();
+    /// assert!(matches!(vm.run(), Err(VMError::MisalignedJump { from: 0, target: 2 })));
+    /// ```
+    #[inline]
+    pub fn enable_alignment_check(&mut self) {
+        self.instruction_starts = Some(instr::linear_instruction_starts(&self.memory));
+    }
+}
+
+impl<T, M> IntcodeVM<T, M>
+where
+    T: Integer + Clone + ToPrimitive,
+    M: MemoryStore<T>,
+{
+    /// Creates a new VM executing the program in `memory`
+    pub(crate) fn with_memory(memory: M) -> Self {
+        Self {
+            initial_memory: Arc::new(memory.clone()),
+            memory,
+            instruction_ptr: 0,
+            relative_base_ptr: T::zero(),
+            next_input_value: None,
+            input_queue: VecDeque::new(),
+            buffer_output: false,
+            output_address: None,
+            output_buffer: Vec::new(),
+            trace: None,
+            profile_accesses: false,
+            access_profile: BTreeMap::new(),
+            profile_opcodes: false,
+            opcode_counts: OpcodeCounts::default(),
+            instruction_starts: None,
+            halt_behavior: HaltBehavior::Terminate,
+            input_on_empty: EmptyInputBehavior::WaitForInput,
+            input_log: None,
+            peak_memory_len: None,
+            instructions_executed: 0,
+            max_memory: None,
+            strict_memory: false,
+            code_limit: None,
+            instruction_limit: None,
+            fuel: None,
+            checked_arithmetic: None,
+            trace_hook: Hook::default(),
+            output_hook: Hook::default(),
+            input_iter: Hook::default(),
+            breakpoints: BTreeSet::new(),
+            resumed_breakpoint: None,
+            watchpoints: BTreeSet::new(),
+            watchpoint_hit: None,
+            history: None,
+        }
+    }
+
+    /// Executes the intcode program in the memory of the VM
+    ///
+    /// When a halt instruction is encountered, returns [`Ok(VMResult::Halted)`](VMResult::Halted)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([1, 0, 0, 3, 99]);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// ```
+    ///
+    /// Will return a [VMError] if a problem occurred, such as an unrecognized op code
+    /// ```
+    /// # use intcode_vm::IntcodeVM;
+    /// let mut vm = IntcodeVM::new([15]); // 15 is not a valid op code
+    /// assert!(vm.run().is_err());
+    /// ```
+    #[inline]
+    pub fn run(&mut self) -> error::Result<VMResult<T>, T> {
+        loop {
+            if let Some(result) = self.execute_buffered()? {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Executes the program like [`vm.run()`](IntcodeVM::run), executing at most `max_instructions` instructions
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VMError::InstructionLimitExceeded`] once `max_instructions` instructions have been executed
+    /// without the VM stopping, the VM is left in the state it reached.
+    /// As with [`IntcodeVM::set_instruction_limit()`](IntcodeVM::set_instruction_limit), the error holds
+    /// the total number of instructions executed by the VM (including the previous runs).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::VMError, IntcodeVM, VMResult};
+    /// // loops forever
+    /// let mut vm = IntcodeVM::new([1105, 1, 0]);
+    /// assert!(matches!(
+    ///     vm.run_with_limit(100),
+    ///     Err(VMError::InstructionLimitExceeded { executed: 100 })
+    /// ));
+    /// assert_eq!(vm.instructions_executed(), 100);
+    ///
+    /// assert!(matches!(
+    ///     vm.run_with_limit(100),
+    ///     Err(VMError::InstructionLimitExceeded { executed: 200 })
+    /// ));
+    ///
+    /// let mut vm = IntcodeVM::new([104, 1, 99]);
+    /// assert_eq!(vm.run_with_limit(100).unwrap(), VMResult::Output(1));
+    /// ```
+    pub fn run_with_limit(&mut self, max_instructions: u64) -> error::Result<VMResult<T>, T> {
+        for _ in 0..max_instructions {
+            if let Some(result) = self.execute_buffered()? {
+                return Ok(result);
+            }
+        }
+
+        Err(VMError::InstructionLimitExceeded {
+            executed: self.instructions_executed,
+        })
+    }
+
+    /// Makes the VM return [`VMError::InstructionLimitExceeded`] instead of executing an instruction
+    /// once it executed `limit` instructions in total (see [`IntcodeVM::instructions_executed()`](IntcodeVM::instructions_executed))
+    ///
+    /// Unlike [`IntcodeVM::run_with_limit()`](IntcodeVM::run_with_limit),
+    /// the limit applies to every way of executing the program. There is no limit by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::VMError, IntcodeVM, VMResult};
+    /// // outputs 1 then loops forever
+    /// let mut vm = IntcodeVM::new([104, 1, 1105, 1, 2]);
+    /// vm.set_instruction_limit(50);
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(1));
+    /// assert!(matches!(
+    ///     vm.run(),
+    ///     Err(VMError::InstructionLimitExceeded { executed: 50 })
+    /// ));
+    /// ```
+    #[inline]
+    pub fn set_instruction_limit(&mut self, limit: u64) {
+        self.instruction_limit = Some(limit);
+    }
+
+    /// Gives the VM a budget of `fuel` instructions, replacing the remaining one
+    ///
+    /// Each executed instruction uses one unit of fuel, the budget is kept across calls to [`vm.run()`](IntcodeVM::run).
+    /// Once it is exhausted, the VM stops with [`VMResult::OutOfFuel`] and can be resumed after
+    /// [refueling](IntcodeVM::add_fuel), unlike the [instruction limit](IntcodeVM::set_instruction_limit)
+    /// whose error is final. This allows running many VMs in turn on a single thread.
+    /// The fuel is unlimited by default.
+    ///
+    /// The helpers running a program to completion (such as [`IntcodeVM::run_to_halt()`](IntcodeVM::run_to_halt))
+    /// return [`VMError::OutOfFuel`] if it runs out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// // adds 1 to its counter then outputs it, forever
+    /// let mut vm = IntcodeVM::new([1001, 9, 1, 9, 4, 9, 1105, 1, 0]);
+    /// vm.set_fuel(5);
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(1));
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(2));
+    /// assert_eq!(vm.run().unwrap(), VMResult::OutOfFuel);
+    /// assert_eq!(vm.fuel(), Some(0));
+    ///
+    /// vm.add_fuel(3);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(3));
+    /// assert_eq!(vm.run().unwrap(), VMResult::OutOfFuel);
+    /// ```
+    #[inline]
+    pub fn set_fuel(&mut self, fuel: u64) {
+        self.fuel = Some(fuel);
+    }
+
+    /// Adds `delta` to the fuel of the VM (see [`IntcodeVM::set_fuel()`](IntcodeVM::set_fuel)),
+    /// starting from an empty budget if the fuel was unlimited
+    #[inline]
+    pub fn add_fuel(&mut self, delta: u64) {
+        self.fuel = Some(self.fuel.unwrap_or(0).saturating_add(delta));
+    }
+
+    /// Remaining fuel of the VM, [`None`] if it is unlimited (see [`IntcodeVM::set_fuel()`](IntcodeVM::set_fuel))
+    #[inline]
+    pub const fn fuel(&self) -> Option<u64> {
+        self.fuel
+    }
+
+    /// Total number of instructions executed by the VM
+    #[inline]
+    pub const fn instructions_executed(&self) -> u64 {
+        self.instructions_executed
+    }
+
+    /// Executes a single instruction
+    ///
+    /// Returns the same results as [`vm.run()`](IntcodeVM::run) would for that instruction,
+    /// or [`VMResult::Continue`] for instructions that would not have stopped it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([1101, 2, 3, 7, 4, 7, 99, 0]);
+    ///
+    /// assert_eq!(vm.step().unwrap(), VMResult::Continue);
+    /// assert_eq!(vm.instruction_pointer(), 4);
+    /// assert_eq!(vm.step().unwrap(), VMResult::Output(5));
+    /// assert_eq!(vm.step().unwrap(), VMResult::Halted);
+    /// ```
+    #[inline]
+    pub fn step(&mut self) -> error::Result<VMResult<T>, T> {
+        Ok(self.execute_buffered()?.unwrap_or(VMResult::Continue))
+    }
+
+    /// Makes the VM stop with [`VMResult::BreakpointHit`] when it reaches the instruction at `address`,
+    /// before executing it
+    ///
+    /// Resuming the execution from a breakpoint executes its instruction,
+    /// the breakpoint is only hit again once the instruction pointer comes back to it.
+    /// The helpers running a program to completion (such as [`IntcodeVM::run_to_halt()`](IntcodeVM::run_to_halt))
+    /// resume past breakpoints.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// // outputs 3, 2, 1
+    /// let mut vm = IntcodeVM::new([4, 10, 1001, 10, -1, 10, 1005, 10, 0, 99, 3]);
+    /// vm.add_breakpoint(2);
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(3));
+    /// assert_eq!(vm.run().unwrap(), VMResult::BreakpointHit(2));
+    /// assert_eq!(vm.instruction_pointer(), 2);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(2));
+    /// assert_eq!(vm.run().unwrap(), VMResult::BreakpointHit(2));
+    ///
+    /// vm.remove_breakpoint(2);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(1));
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// ```
+    #[inline]
+    pub fn add_breakpoint(&mut self, address: usize) {
+        self.breakpoints.insert(address);
+    }
+
+    /// Removes the breakpoint at `address` (see [`IntcodeVM::add_breakpoint()`](IntcodeVM::add_breakpoint))
+    #[inline]
+    pub fn remove_breakpoint(&mut self, address: usize) {
+        self.breakpoints.remove(&address);
+    }
+
+    /// Makes the VM stop with [`VMResult::WatchpointHit`] right after an instruction writes to the cell at `address`
+    /// (even if the value written is the one already there)
+    ///
+    /// Just like breakpoints, watchpoints are resumed past by the helpers running a program to completion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// // overwrites its own halt instruction with an output instruction
+    /// let mut vm = IntcodeVM::new([1101, 2, 2, 5, 99, 99]);
+    /// vm.add_watchpoint(5);
+    ///
+    /// assert_eq!(
+    ///     vm.run().unwrap(),
+    ///     VMResult::WatchpointHit { address: 5, old: 99, new: 4 }
+    /// );
+    /// assert_eq!(vm.instruction_pointer(), 4);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// ```
+    #[inline]
+    pub fn add_watchpoint(&mut self, address: usize) {
+        self.watchpoints.insert(address);
+    }
+
+    /// Removes the watchpoint at `address` (see [`IntcodeVM::add_watchpoint()`](IntcodeVM::add_watchpoint))
+    #[inline]
+    pub fn remove_watchpoint(&mut self, address: usize) {
+        self.watchpoints.remove(&address);
+    }
+
+    /// Runs the program until its next output
+    ///
+    /// Returns [`None`] if the program halts (or pauses) before outputting anything,
+    /// the execution goes on past [breakpoints](IntcodeVM::add_breakpoint) and [watchpoints](IntcodeVM::add_watchpoint).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VMError::UnexpectedInputRequest`] if the program asks for an input while the input queue is empty
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::VMError, IntcodeVM};
+    /// let mut vm = IntcodeVM::new([104, 1, 104, 2, 99]);
+    /// assert_eq!(vm.next_output().unwrap(), Some(1));
+    /// assert_eq!(vm.next_output().unwrap(), Some(2));
+    /// assert_eq!(vm.next_output().unwrap(), None);
+    ///
+    /// let mut vm = IntcodeVM::new([3, 0, 4, 0, 99]);
+    /// assert!(matches!(vm.next_output(), Err(VMError::UnexpectedInputRequest)));
+    /// ```
+    pub fn next_output(&mut self) -> error::Result<Option<T>, T> {
+        loop {
+            match self.run()? {
+                VMResult::OutOfFuel => return Err(VMError::OutOfFuel),
+                VMResult::Output(out) => return Ok(Some(out)),
+                VMResult::WaitingForInput => return Err(VMError::UnexpectedInputRequest),
+                VMResult::Halted | VMResult::Paused => return Ok(None),
+                _ => (),
+            }
+        }
+    }
+
+    /// Runs the program until it outputted `n` values (such as the `color, turn` pairs of day 11), returning them
+    ///
+    /// The execution stops right after the `n`th output, it can be resumed to get the next ones.
+    ///
+    /// # Errors
+    ///
+    /// - [`VMError::HaltedBeforeOutput`] if the program halts (or pauses) before outputting `n` values,
+    ///   along with the number of values it outputted
+    /// - [`VMError::UnexpectedInputRequest`] if the program asks for an input while the input queue is empty
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::VMError, IntcodeVM};
+    /// let mut vm = IntcodeVM::new([104, 1, 104, 2, 104, 3, 99]);
+    /// assert_eq!(vm.run_n_outputs(2).unwrap(), [1, 2]);
+    /// assert!(matches!(vm.run_n_outputs(2), Err(VMError::HaltedBeforeOutput { collected: 1 })));
+    /// ```
+    pub fn run_n_outputs(&mut self, n: usize) -> error::Result<Vec<T>, T> {
+        let mut outputs = Vec::with_capacity(n);
+        while outputs.len() < n {
+            match self.next_output()? {
+                Some(out) => outputs.push(out),
+                None => {
+                    return Err(VMError::HaltedBeforeOutput {
+                        collected: outputs.len(),
+                    })
+                }
+            }
+        }
+
+        Ok(outputs)
+    }
+
+    /// Runs the program to halt, gathering its outputs in chunks of `N` (such as the `x, y, tile` triples of day 13)
+    ///
+    /// `N` must not be 0, using an empty chunk size does not compile.
+    ///
+    /// # Errors
+    ///
+    /// - [`VMError::IncompleteOutputChunk`] if the number of outputs is not a multiple of `N`
+    /// - [`VMError::UnexpectedInputRequest`] if the program asks for an input while the input queue is empty
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::VMError, IntcodeVM};
+    /// let mut vm = IntcodeVM::new([104, 1, 104, 2, 104, 3, 104, 4, 99]);
+    /// assert_eq!(vm.collect_output_chunks::<2>().unwrap(), [[1, 2], [3, 4]]);
+    ///
+    /// let mut vm = IntcodeVM::new([104, 1, 104, 2, 104, 3, 104, 4, 99]);
+    /// assert!(matches!(
+    ///     vm.collect_output_chunks::<3>(),
+    ///     Err(VMError::IncompleteOutputChunk { size: 3, leftover: 1 })
+    /// ));
+    /// ```
+    pub fn collect_output_chunks<const N: usize>(&mut self) -> error::Result<Vec<[T; N]>, T> {
+        const { assert!(N > 0, "the outputs cannot be collected in empty chunks") };
+
+        let mut chunks = Vec::new();
+        let mut chunk = Vec::with_capacity(N);
+        while let Some(out) = self.next_output()? {
+            chunk.push(out);
+            if chunk.len() == N {
+                let full =
+                    <[T; N]>::try_from(core::mem::replace(&mut chunk, Vec::with_capacity(N)))
+                        .ok()
+                        .expect("the chunk holds exactly N outputs");
+                chunks.push(full);
+            }
+        }
+
+        if !chunk.is_empty() {
+            return Err(VMError::IncompleteOutputChunk {
+                size: N,
+                leftover: chunk.len(),
+            });
+        }
+
+        Ok(chunks)
+    }
+
+    /// Runs the program until it halts, calling `input` whenever it asks for an input
+    /// and `output` with every value it outputs
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::IntcodeVM;
+    /// let mut vm = IntcodeVM::new([
+    ///     3, 16, 1002, 16, 3, 16, 4, 16, // outputs its input times 3
+    ///     1001, 17, -1, 17, 1005, 17, 0, // decrements the counter at 17, starts over if it is not 0
+    ///     99, 0, 2,
+    /// ]);
+    /// let mut inputs = [2, 5].into_iter();
+    /// let mut outputs = Vec::new();
+    ///
+    /// vm.run_to_halt(|| inputs.next().unwrap(), |out| outputs.push(out))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(outputs, [6, 15]);
+    /// ```
+    pub fn run_to_halt<FI, FO>(&mut self, mut input: FI, mut output: FO) -> error::Result<(), T>
+    where
+        FI: FnMut() -> T,
+        FO: FnMut(T),
+    {
+        loop {
+            match self.run()? {
+                VMResult::OutOfFuel => return Err(VMError::OutOfFuel),
+                VMResult::WaitingForInput => {
+                    self.set_next_input(input());
+                }
+                VMResult::Output(out) => output(out),
+                VMResult::BreakpointHit(_) | VMResult::WatchpointHit { .. } => (),
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Runs the program until it halts or waits for an input while the input queue is empty,
+    /// returning every value it outputted along with whether it halted (`false` if it is waiting for an input)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::IntcodeVM;
+    /// // outputs its input times 3
+    /// let mut vm = IntcodeVM::new([3, 9, 1002, 9, 3, 9, 4, 9, 99, 0]);
+    /// assert_eq!(vm.run_collecting().unwrap(), (vec![], false));
+    ///
+    /// vm.push_input(14);
+    /// assert_eq!(vm.run_collecting().unwrap(), (vec![42], true));
+    /// ```
+    pub fn run_collecting(&mut self) -> error::Result<(Vec<T>, bool), T> {
+        let mut outputs = Vec::new();
+        loop {
+            match self.run()? {
+                VMResult::OutOfFuel => return Err(VMError::OutOfFuel),
+                VMResult::Output(out) => outputs.push(out),
+                VMResult::WaitingForInput => return Ok((outputs, false)),
+                VMResult::Halted | VMResult::Paused => return Ok((outputs, true)),
+                _ => (),
+            }
+        }
+    }
+
+    /// Runs the program until it halts (discarding its outputs), then returns the value of the cell at `address`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`VMError::UnexpectedInputRequest`] if the program asks for an input while the input queue is empty
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::VMError, IntcodeVM};
+    /// let mut vm = IntcodeVM::new([1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50]);
+    /// assert_eq!(vm.run_to_halt_and_read(0).unwrap(), 3500);
+    ///
+    /// let mut vm = IntcodeVM::new([3, 0, 99]);
+    /// assert!(matches!(
+    ///     vm.run_to_halt_and_read(0),
+    ///     Err(VMError::UnexpectedInputRequest)
+    /// ));
+    /// ```
+    pub fn run_to_halt_and_read(&mut self, address: usize) -> error::Result<T, T> {
+        loop {
+            match self.run()? {
+                VMResult::OutOfFuel => return Err(VMError::OutOfFuel),
+                VMResult::WaitingForInput => return Err(VMError::UnexpectedInputRequest),
+                VMResult::Halted | VMResult::Paused => return Ok(self.memory.get(address).clone()),
+                _ => (),
+            }
+        }
+    }
+
+    /// Runs the program to halt with the given `inputs`, checking its outputs are `expected`
+    ///
+    /// Shorthand for [`IntcodeVM::run_expect_approx()`](IntcodeVM::run_expect_approx) using `==`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::IntcodeVM;
+    /// // outputs 1 if the input is 8, 0 otherwise
+    /// let program = [3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8];
+    /// assert!(IntcodeVM::new(program).run_expect(&[8], &[1]).is_ok());
+    /// assert!(IntcodeVM::new(program).run_expect(&[7], &[0]).is_ok());
+    /// assert!(IntcodeVM::new(program).run_expect(&[7], &[1]).is_err());
+    /// ```
+    #[inline]
+    pub fn run_expect(&mut self, inputs: &[T], expected: &[T]) -> error::Result<(), T> {
+        self.run_expect_approx(inputs, expected, T::eq)
+    }
+
+    /// Runs the program to halt with the given `inputs`, checking its outputs match `expected`
+    /// according to `eq` (called with the actual output first, then the expected one)
+    ///
+    /// # Errors
+    ///
+    /// - [`VMError::OutputMismatch`] if an output does not match, or if there are too many or too few outputs
+    /// - [`VMError::UnexpectedInputRequest`] if the program asks for more inputs than provided
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::IntcodeVM;
+    /// let program = [104, 1001, 104, 1999, 99];
+    /// let roughly_eq = |actual: &i32, expected: &i32| (actual - expected).abs() < 5;
+    ///
+    /// assert!(IntcodeVM::new(program)
+    ///     .run_expect_approx(&[], &[1000, 2000], roughly_eq)
+    ///     .is_ok());
+    /// assert!(IntcodeVM::new(program)
+    ///     .run_expect_approx(&[], &[1010, 2000], roughly_eq)
+    ///     .is_err());
+    /// ```
+    pub fn run_expect_approx<F: Fn(&T, &T) -> bool>(
+        &mut self,
+        inputs: &[T],
+        expected: &[T],
+        eq: F,
+    ) -> error::Result<(), T> {
+        let mut inputs = inputs.iter();
+        let mut expected_outputs = expected.iter().enumerate();
+        loop {
+            match self.run()? {
+                VMResult::OutOfFuel => return Err(VMError::OutOfFuel),
+                VMResult::WaitingForInput => {
+                    let input = inputs.next().ok_or(VMError::UnexpectedInputRequest)?;
+                    self.set_next_input(input.clone());
+                }
+                VMResult::Output(out) => match expected_outputs.next() {
+                    Some((_, expected)) if eq(&out, expected) => (),
+                    Some((index, expected)) => {
+                        return Err(VMError::OutputMismatch {
+                            index,
+                            expected: Some(expected.clone()),
+                            actual: Some(out),
+                        })
+                    }
+                    None => {
+                        return Err(VMError::OutputMismatch {
+                            index: expected.len(),
+                            expected: None,
+                            actual: Some(out),
+                        })
+                    }
+                },
+                VMResult::BreakpointHit(_) | VMResult::WatchpointHit { .. } => (),
+                _ => break,
+            }
+        }
+
+        match expected_outputs.next() {
+            Some((index, expected)) => Err(VMError::OutputMismatch {
+                index,
+                expected: Some(expected.clone()),
+                actual: None,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Runs the program, collecting its outputs, and calls `decide` with all the outputs collected so far
+    /// whenever it asks for an input
+    ///
+    /// If `decide` returns `Some(input)`, `input` is provided to the program and the execution continues,
+    /// if it returns [`None`] the execution stops.
+    /// Returns the collected outputs once the program halts or `decide` stops it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::IntcodeVM;
+    /// // outputs its input + 1, forever
+    /// let mut vm = IntcodeVM::new([3, 9, 1001, 9, 1, 9, 4, 9, 1105, 1, 0]);
+    /// let outputs = vm
+    ///     .run_reactive(|outputs| match outputs.last() {
+    ///         Some(&last) if last >= 5 => None,
+    ///         Some(&last) => Some(last),
+    ///         None => Some(0),
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(outputs, [1, 2, 3, 4, 5]);
+    /// ```
+    pub fn run_reactive<F: FnMut(&[T]) -> Option<T>>(
+        &mut self,
+        mut decide: F,
+    ) -> error::Result<Vec<T>, T> {
+        let mut outputs = Vec::new();
+        loop {
+            match self.run()? {
+                VMResult::OutOfFuel => return Err(VMError::OutOfFuel),
+                VMResult::Output(out) => outputs.push(out),
+                VMResult::WaitingForInput => match decide(&outputs) {
+                    Some(input) => {
+                        self.set_next_input(input);
+                    }
+                    None => return Ok(outputs),
+                },
+                VMResult::BreakpointHit(_) | VMResult::WatchpointHit { .. } => (),
+                _ => return Ok(outputs),
+            }
+        }
+    }
+
+    /// Counts the instructions that will be executed before the next output,
+    /// without modifying this VM (the execution is done on a clone)
+    ///
+    /// Returns [`None`] if the program halts or asks for an input (with none provided) before outputting anything.
+    ///
+    /// # Note
+    ///
+    /// Just like [`IntcodeVM::run()`](IntcodeVM::run), this never returns if the program loops forever
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::IntcodeVM;
+    /// let vm = IntcodeVM::new([1101, 2, 3, 5, 104, 0, 99]);
+    /// assert_eq!(vm.instructions_to_next_output().unwrap(), Some(1));
+    ///
+    /// let vm = IntcodeVM::new([3, 0, 104, 0, 99]);
+    /// assert_eq!(vm.instructions_to_next_output().unwrap(), None);
+    /// ```
+    pub fn instructions_to_next_output(&self) -> error::Result<Option<u64>, T> {
+        let mut vm = self.clone();
+        let mut executed = 0;
+        loop {
+            match vm.execute_instruction()? {
+                None => executed += 1,
+                Some(VMResult::Output(_)) => return Ok(Some(executed)),
+                Some(_) => return Ok(None),
+            }
+        }
+    }
+
+    /// Decodes the instruction at `address` without executing it
+    ///
+    /// # Errors
+    ///
+    /// Returns the error executing it would return if the cell at `address` is not a valid instruction
+    /// (such as [`VMError::UnknownInstruction`])
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::VMError, IntcodeVM};
+    /// let vm = IntcodeVM::new([1101, 4, 3, 5, 204, -1, 99]);
+    ///
+    /// let add = vm.decode_at(0).unwrap();
+    /// assert_eq!(add.opcode, 1);
+    /// assert_eq!(add.operands, [(1, 4), (1, 3), (0, 5)]);
+    /// assert_eq!(add.width(), 4);
+    ///
+    /// let output = vm.decode_at(4).unwrap();
+    /// assert_eq!(output.operands, [(2, -1)]);
+    ///
+    /// // -1 is not an instruction
+    /// assert!(matches!(vm.decode_at(5), Err(VMError::CannotCastToU16 { value: -1, address: 5 })));
+    /// ```
+    pub fn decode_at(&self, address: usize) -> error::Result<DecodedInstruction<T>, T> {
+        instr::Instruction::decode(&self.memory, address)
+            .map(|instruction| instruction.to_decoded(address))
+    }
+
+    /// Executes the instruction at the instruction pointer
+    ///
+    /// Returns `Ok(None)` if the execution can continue with the next instruction
+    fn execute_instruction(&mut self) -> error::Result<Option<VMResult<T>>, T> {
+        let instruction_ptr = self.instruction_ptr;
+        if self
+            .code_limit
+            .is_some_and(|limit| instruction_ptr >= limit)
+        {
+            return Err(VMError::InstructionPointerOutOfRange(instruction_ptr));
+        }
+
+        if self
+            .instruction_limit
+            .is_some_and(|limit| self.instructions_executed >= limit)
+        {
+            return Err(VMError::InstructionLimitExceeded {
+                executed: self.instructions_executed,
+            });
+        }
+
+        if self.fuel == Some(0) {
+            return Ok(Some(VMResult::OutOfFuel));
+        }
+
+        if let Some(hook) = self.trace_hook.get_mut() {
+            let word = self.memory.get(instruction_ptr).clone();
+            if let Some(full_opcode) = word.to_u16() {
+                hook(TraceEvent {
+                    ip: instruction_ptr,
+                    opcode: full_opcode % 100,
+                    modes: [
+                        (full_opcode / 100 % 10) as u8,
+                        (full_opcode / 1000 % 10) as u8,
+                        (full_opcode / 10000) as u8,
+                    ],
+                    word,
+                });
+            }
+        }
+
+        if self.strict_memory {
+            self.check_in_bounds(instruction_ptr)?;
+        }
+
+        let state_before = self.history.as_mut().map(|history| {
+            history.current_write = None;
+            (self.relative_base_ptr.clone(), self.memory.len())
+        });
+        let instruction = instr::Instruction::from_current_instr_ptr(self)?;
+        #[cfg(feature = "log")]
+        instruction.log_execution(instruction_ptr, self);
+        let instruction_width = instruction.instruction_width();
+        let opcode = instruction.opcode();
+        let mut consumed_input = None;
+        let reads = if self.profile_accesses || self.strict_memory {
+            instruction.read_addresses(self)?
+        } else {
+            [None, None]
+        };
+
+        if self.strict_memory {
+            // the first cell of the instruction is in bounds, the first one missing is the end of the memory
+            if instruction_ptr + instruction_width > self.memory.len() {
+                return Err(VMError::AddressOutOfBounds(self.memory.len()));
+            }
+            for &address in reads.iter().flatten() {
+                self.check_in_bounds(address)?;
+            }
+        }
+
+        let result = match instruction {
+            instr::Instruction::Add(arg1, arg2, dest) => {
+                let arg1_val = arg1.resolve_value(self)?;
+                let arg2_val = arg2.resolve_value(self)?;
+                let destination_addr = dest.resolve_address(self)?;
+
+                let result = match &self.checked_arithmetic {
+                    Some(ops) => {
+                        (ops.add)(arg1_val, arg2_val).ok_or(VMError::ArithmeticOverflow {
+                            opcode,
+                            ip: instruction_ptr,
+                        })?
+                    }
+                    None => arg1_val.clone() + arg2_val.clone(),
+                };
+                self.write_memory(destination_addr, result)?;
+                self.increment_instr_ptr_by(instruction_width);
+                None
+            }
+
+            instr::Instruction::Mul(arg1, arg2, dest) => {
+                let arg1_val = arg1.resolve_value(self)?;
+                let arg2_val = arg2.resolve_value(self)?;
+                let destination_addr = dest.resolve_address(self)?;
+
+                let result = match &self.checked_arithmetic {
+                    Some(ops) => {
+                        (ops.mul)(arg1_val, arg2_val).ok_or(VMError::ArithmeticOverflow {
+                            opcode,
+                            ip: instruction_ptr,
+                        })?
+                    }
+                    None => arg1_val.clone() * arg2_val.clone(),
+                };
+                self.write_memory(destination_addr, result)?;
+                self.increment_instr_ptr_by(instruction_width);
+                None
+            }
+
+            instr::Instruction::ReadInput(dest) => {
+                let destination_addr = dest.resolve_address(self)?;
+                self.check_memory_limit(destination_addr)?;
+                let input = match self.next_input_value.take() {
+                    Some(input) => {
+                        self.next_input_value = self.input_queue.pop_front();
+                        Some(input)
+                    }
+                    None => self.input_iter.get_mut().and_then(Iterator::next),
+                };
+                if state_before.is_some() {
+                    consumed_input.clone_from(&input);
+                }
+                let input = input.or_else(|| match &self.input_on_empty {
+                    EmptyInputBehavior::WaitForInput => None,
+                    EmptyInputBehavior::Yield(value) => Some(value.clone()),
+                });
+
+                if let Some(input) = input {
+                    #[cfg(feature = "log")]
+                    log::debug!("ip={instruction_ptr} input={}", LogValue(&input));
+                    if let Some(log) = &mut self.input_log {
+                        log.push((destination_addr, input.clone()));
+                    }
+
+                    self.write_memory(destination_addr, input)?;
+                    self.increment_instr_ptr_by(instruction_width);
+                    None
+                } else {
+                    #[cfg(feature = "log")]
+                    log::debug!("ip={instruction_ptr} waiting for input");
+                    // the instruction was not executed, it will be once an input is provided
+                    return Ok(Some(VMResult::WaitingForInput));
+                }
+            }
+
+            instr::Instruction::WriteOutput(arg) => {
+                let res = arg.resolve_value(self)?.clone();
+                #[cfg(feature = "log")]
+                log::debug!("ip={instruction_ptr} output={}", LogValue(&res));
+                if let Some(address) = self.output_address {
+                    self.write_memory(address, res)?;
+                    self.increment_instr_ptr_by(instruction_width);
+                    None
+                } else {
+                    self.increment_instr_ptr_by(instruction_width);
+                    Some(VMResult::Output(res))
+                }
+            }
+
+            instr::Instruction::JmpIfTrue(arg, target) => {
+                if !arg.resolve_value(self)?.is_zero() {
+                    let target_value = target.resolve_value(self)?;
+                    let new_instr_ptr = instr::to_address(target_value.clone())?;
+
+                    self.jump_to(instruction_ptr, new_instr_ptr)?;
+                } else {
+                    self.increment_instr_ptr_by(instruction_width);
+                }
+                None
+            }
+
+            instr::Instruction::JmpIfFalse(arg, target) => {
+                if arg.resolve_value(self)?.is_zero() {
+                    let target_value = target.resolve_value(self)?;
+                    let new_instr_ptr = instr::to_address(target_value.clone())?;
+
+                    self.jump_to(instruction_ptr, new_instr_ptr)?;
+                } else {
+                    self.increment_instr_ptr_by(instruction_width);
+                }
+                None
+            }
+
+            instr::Instruction::LessThan(arg1, arg2, result) => {
+                let arg1_val = arg1.resolve_value(self)?;
+                let arg2_val = arg2.resolve_value(self)?;
+                let dest = result.resolve_address(self)?;
+                if arg1_val < arg2_val {
+                    self.write_memory(dest, T::one())?;
+                } else {
+                    self.write_memory(dest, T::zero())?;
+                }
+
+                self.increment_instr_ptr_by(instruction_width);
+                None
+            }
+
+            instr::Instruction::Equals(arg1, arg2, result) => {
+                let arg1_val = arg1.resolve_value(self)?;
+                let arg2_val = arg2.resolve_value(self)?;
+                let dest = result.resolve_address(self)?;
+                if arg1_val == arg2_val {
+                    self.write_memory(dest, T::one())?;
+                } else {
+                    self.write_memory(dest, T::zero())?;
+                }
+
+                self.increment_instr_ptr_by(instruction_width);
+                None
+            }
+
+            instr::Instruction::AddRelativeBase(arg) => {
+                let arg_val = arg.resolve_value(self)?;
+                self.relative_base_ptr = self.relative_base_ptr.clone() + arg_val.clone();
+
+                self.increment_instr_ptr_by(instruction_width);
+                None
+            }
+
+            instr::Instruction::Halt => {
+                #[cfg(feature = "log")]
+                log::debug!("ip={instruction_ptr} halt");
+                Some(match self.halt_behavior {
+                    HaltBehavior::Terminate => VMResult::Halted,
+                    HaltBehavior::Pause => VMResult::Paused,
+                })
+            }
+        };
+
+        if self.profile_accesses {
+            for address in reads.into_iter().flatten() {
+                self.access_profile.entry(address).or_default().0 += 1;
+            }
+        }
+
+        if let (Some(history), Some((relative_base_ptr, memory_len))) =
+            (&mut self.history, state_before)
+        {
+            let write = history.current_write.take();
+            // a halt does not change anything, there is nothing to undo
+            if opcode != 99 && history.depth > 0 {
+                if history.entries.len() == history.depth {
+                    history.entries.pop_front();
+                }
+                history.entries.push_back(HistoryEntry {
+                    instruction_ptr,
+                    relative_base_ptr,
+                    memory_len,
+                    write,
+                    input: consumed_input,
+                });
+            }
+        }
+
+        self.instructions_executed += 1;
+        if let Some(fuel) = &mut self.fuel {
+            *fuel -= 1;
+        }
+        if self.profile_opcodes {
+            self.opcode_counts.record(opcode);
+        }
+
+        if let Some(trace) = &mut self.trace {
+            trace.record(instruction_ptr, result.as_ref());
+        }
+
+        Ok(result)
+    }
+
+    /// Executes the instruction at the instruction pointer like
+    /// [`IntcodeVM::execute_instruction()`](IntcodeVM::execute_instruction),
+    /// adding the output to the output buffer (instead of returning it) if enabled
+    ///
+    /// Stops with [`VMResult::BreakpointHit`] instead if there is a breakpoint at the instruction pointer
+    /// (unless the execution is resuming from it), and with [`VMResult::WatchpointHit`]
+    /// after the instruction if it wrote to a watched cell
+    #[inline]
+    fn execute_buffered(&mut self) -> error::Result<Option<VMResult<T>>, T> {
+        let instruction_ptr = self.instruction_ptr;
+        let resuming = self.resumed_breakpoint.take() == Some(instruction_ptr);
+        if !resuming && self.breakpoints.contains(&instruction_ptr) {
+            self.resumed_breakpoint = Some(instruction_ptr);
+            return Ok(Some(VMResult::BreakpointHit(instruction_ptr)));
+        }
+
+        let result = self.execute_instruction()?;
+        if resuming
+            && matches!(
+                result,
+                Some(
+                    VMResult::WaitingForInput
+                        | VMResult::Halted
+                        | VMResult::Paused
+                        | VMResult::OutOfFuel
+                )
+            )
+        {
+            // the instruction pointer did not move, still resuming from the breakpoint
+            self.resumed_breakpoint = Some(instruction_ptr);
+        }
+
+        if let Some((address, old, new)) = self.watchpoint_hit.take() {
+            return Ok(Some(VMResult::WatchpointHit { address, old, new }));
+        }
+
+        Ok(match result {
+            Some(VMResult::Output(out)) => match self.output_hook.get_mut() {
+                Some(hook) => {
+                    hook(out);
+                    None
+                }
+                None if self.buffer_output => {
+                    self.output_buffer.push(out);
+                    None
+                }
+                None => Some(VMResult::Output(out)),
+            },
+            result => result,
+        })
+    }
+
+    /// Runs a diagnostic program (as described in day 5) with `input` as its only input
+    ///
+    /// `input` is added at the back of the input queue (see [`IntcodeVM::push_input()`](IntcodeVM::push_input)).
+    ///
+    /// The program is run to halt, every output but the last one must be 0 (a passing test),
+    /// the last output (the diagnostic code) is returned.
+    ///
+    /// # Errors
+    ///
+    /// - [`VMError::DiagnosticFailed`] if one of the tests outputs a non-zero value
+    /// - [`VMError::UnexpectedInputRequest`] if the program asks for more than one input
+    /// - [`VMError::HaltedBeforeOutput`] if the program halts without outputting anything
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::IntcodeVM;
+    /// // outputs 0 twice, then its input
+    /// let mut vm = IntcodeVM::new([104, 0, 104, 0, 3, 9, 4, 9, 99, 0]);
+    /// assert_eq!(vm.run_diagnostic(42).unwrap(), 42);
+    ///
+    /// // the first test fails
+    /// let mut vm = IntcodeVM::new([104, 3, 104, 0, 3, 9, 4, 9, 99, 0]);
+    /// assert!(vm.run_diagnostic(42).is_err());
+    /// ```
+    pub fn run_diagnostic(&mut self, input: T) -> error::Result<T, T> {
+        self.push_input(input);
+
+        let mut outputs = Vec::new();
+        loop {
+            match self.run()? {
+                VMResult::OutOfFuel => return Err(VMError::OutOfFuel),
+                VMResult::Output(out) => outputs.push(out),
+                VMResult::WaitingForInput => return Err(VMError::UnexpectedInputRequest),
+                VMResult::BreakpointHit(_) | VMResult::WatchpointHit { .. } => (),
+                _ => break,
+            }
+        }
+
+        let diagnostic_code = outputs
+            .pop()
+            .ok_or(VMError::HaltedBeforeOutput { collected: 0 })?;
+        if let Some((at, value)) = outputs.into_iter().enumerate().find(|(_, v)| !v.is_zero()) {
+            return Err(VMError::DiagnosticFailed { at, value });
+        }
+
+        Ok(diagnostic_code)
+    }
+
+    /// Length of the program the VM was created with
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([1101, 2, 3, 10, 99]);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    ///
+    /// assert_eq!(vm.program_len(), 5);
+    /// assert_eq!(vm.memory_len(), 11);
+    /// ```
+    #[inline]
+    pub fn program_len(&self) -> usize {
+        self.initial_memory.len()
+    }
+
+    /// Number of memory cells currently stored, including the ones written beyond the program
+    /// (see [`IntcodeVM::program_len()`](IntcodeVM::program_len))
+    #[inline]
+    pub fn memory_len(&self) -> usize {
+        self.memory.len()
+    }
+
+    /// Limits the memory to `max` cells: writing at an address greater or equal to `max`
+    /// makes the VM return [`VMError::MemoryLimitExceeded`] instead
+    ///
+    /// The memory is unbounded by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::VMError, IntcodeVM};
+    /// let mut vm = IntcodeVM::<i64>::new([1101, 1, 1, 1_000_000_000_000, 99]);
+    /// vm.set_max_memory(1 << 20);
+    ///
+    /// assert!(matches!(
+    ///     vm.run(),
+    ///     Err(VMError::MemoryLimitExceeded {
+    ///         address: 1_000_000_000_000,
+    ///         limit: 0x100000,
+    ///     })
+    /// ));
+    /// assert_eq!(vm.memory_len(), 5);
+    /// ```
+    #[inline]
+    pub fn set_max_memory(&mut self, max: usize) {
+        self.max_memory = Some(max);
+    }
+
+    /// When `strict` is `true`, reading past the end of the memory (an instruction or an argument)
+    /// makes the VM return [`VMError::AddressOutOfBounds`] instead of reading 0
+    ///
+    /// Writing past the end of the memory still extends it. The memory is not strict by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::VMError, IntcodeVM, VMResult};
+    /// // outputs the cell right after the program
+    /// let mut vm = IntcodeVM::new([4, 3, 99]);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(0));
+    ///
+    /// let mut vm = IntcodeVM::new([4, 3, 99]);
+    /// vm.set_strict_memory(true);
+    /// assert!(matches!(vm.run(), Err(VMError::AddressOutOfBounds(3))));
+    /// ```
+    #[inline]
+    pub fn set_strict_memory(&mut self, strict: bool) {
+        self.strict_memory = strict;
+    }
+
+    /// Limits the code region to the first `limit` cells: executing an instruction at an address
+    /// greater or equal to `limit` (after a jump to an absurd address, for example)
+    /// makes the VM return [`VMError::InstructionPointerOutOfRange`] instead of decoding it
+    ///
+    /// The code region is unbounded by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::VMError, IntcodeVM};
+    /// // jumps to 1000
+    /// let mut vm = IntcodeVM::new([1105, 1, 1000, 99]);
+    /// assert!(matches!(
+    ///     vm.run(),
+    ///     Err(VMError::UnknownInstruction { opcode: 0, address: 1000 })
+    /// ));
+    ///
+    /// let mut vm = IntcodeVM::new([1105, 1, 1000, 99]);
+    /// vm.set_code_limit(4);
+    /// assert!(matches!(vm.run(), Err(VMError::InstructionPointerOutOfRange(1000))));
+    /// ```
+    #[inline]
+    pub fn set_code_limit(&mut self, limit: usize) {
+        self.code_limit = Some(limit);
+    }
+
+    /// When `enabled` is `true`, an addition or multiplication overflowing `T`
+    /// makes the VM return [`VMError::ArithmeticOverflow`] instead of wrapping (or panicking in debug builds)
+    ///
+    /// The arithmetic is not checked by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{error::VMError, IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::<i32>::new([1102, 100_000, 100_000, 0, 99]);
+    /// vm.set_checked_arithmetic(true);
+    /// assert!(matches!(
+    ///     vm.run(),
+    ///     Err(VMError::ArithmeticOverflow { opcode: 2, ip: 0 })
+    /// ));
+    ///
+    /// // fits in an i64
+    /// let mut vm = IntcodeVM::<i64>::new([1102, 100_000, 100_000, 0, 99]);
+    /// vm.set_checked_arithmetic(true);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// ```
+    #[inline]
+    pub fn set_checked_arithmetic(&mut self, enabled: bool)
+    where
+        T: CheckedAdd + CheckedMul,
+    {
+        self.checked_arithmetic = enabled.then_some(CheckedOps {
+            add: T::checked_add,
+            mul: T::checked_mul,
+        });
+    }
+
+    /// Starts tracking the largest number of memory cells stored during the execution
+    /// (see [`IntcodeVM::peak_memory_len()`](IntcodeVM::peak_memory_len))
+    #[inline]
+    pub fn enable_memory_watermark(&mut self) {
+        self.peak_memory_len.get_or_insert(self.memory.len());
+    }
+
+    /// Largest [`IntcodeVM::memory_len()`](IntcodeVM::memory_len) reached since
+    /// [`IntcodeVM::enable_memory_watermark()`](IntcodeVM::enable_memory_watermark) was called
+    ///
+    /// If the watermark is not enabled, this is the current memory length
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([1101, 2, 3, 100, 99]);
+    /// vm.enable_memory_watermark();
+    /// assert_eq!(vm.peak_memory_len(), 5);
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// assert_eq!(vm.peak_memory_len(), 101);
+    /// ```
+    #[inline]
+    pub fn peak_memory_len(&self) -> usize {
+        self.peak_memory_len.unwrap_or_else(|| self.memory.len())
+    }
+
+    /// Restores the program the VM was created with, moving the instruction pointer and relative base back to 0
+    ///
+    /// The pending inputs and the buffered outputs are discarded,
+    /// the options enabled on the VM (such as [tracing](IntcodeVM::enable_trace)) stay enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// // outputs its input times 3
+    /// let mut vm = IntcodeVM::new([3, 9, 1002, 9, 3, 9, 4, 9, 99, 0]);
+    /// for input in 1..=3 {
+    ///     vm.reset();
+    ///     vm.push_input(input);
+    ///     assert_eq!(vm.run().unwrap(), VMResult::Output(input * 3));
+    /// }
+    /// ```
+    pub fn reset(&mut self) {
+        self.memory = M::clone(&self.initial_memory);
+        self.instruction_ptr = 0;
+        self.relative_base_ptr = T::zero();
+        self.next_input_value = None;
+        self.input_queue.clear();
+        self.output_buffer.clear();
+        self.resumed_breakpoint = None;
+        if let Some(history) = &mut self.history {
+            history.entries.clear();
+        }
+    }
+
+    /// Creates an independent copy of the VM, to explore a different execution path
+    ///
+    /// The copy has its own memory and copies of the instruction pointer, relative base,
+    /// input queue and every enabled option (only the initial program is shared):
+    /// running one of them does not affect the other.
+    ///
+    /// The [trace hook](IntcodeVM::set_trace_hook), the [output callback](IntcodeVM::on_output)
+    /// and the [input iterator](IntcodeVM::set_input_iter) are not copied, so a fork of a VM using them
+    /// does not behave like it: its outputs are returned as [`VMResult::Output`]
+    /// and its inputs are not taken from the iterator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// // outputs 1 if the input is 8, 0 otherwise
+    /// let mut vm = IntcodeVM::new([3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8]);
+    /// assert_eq!(vm.run().unwrap(), VMResult::WaitingForInput);
+    ///
+    /// let mut fork = vm.fork();
+    /// fork.push_input(8);
+    /// vm.push_input(7);
+    /// assert_eq!(fork.run().unwrap(), VMResult::Output(1));
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(0));
+    /// ```
+    #[inline]
+    pub fn fork(&self) -> Self {
+        self.clone()
+    }
+
+    /// Memory of the VM, as modified by the execution so far
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([1, 0, 0, 3, 99]);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// assert_eq!(vm.memory().get(3), &2);
+    /// ```
+    #[inline]
+    pub const fn memory(&self) -> &M {
+        &self.memory
+    }
+
+    /// Mutable access to the memory of the VM, to patch cells between two runs
+    ///
+    /// The changes are made directly: they are not checked against the [memory limit](IntcodeVM::set_max_memory),
+    /// do not trigger watchpoints and cannot be undone by [`IntcodeVM::step_back()`](IntcodeVM::step_back).
+    /// The instruction starts computed by [`IntcodeVM::enable_alignment_check()`](IntcodeVM::enable_alignment_check)
+    /// are not updated either.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// // outputs the value of the cell at address 5, twice
+    /// let mut vm = IntcodeVM::new([4, 5, 4, 5, 99, 1]);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(1));
+    ///
+    /// vm.memory_mut().set(5, 2);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(2));
+    /// ```
+    #[inline]
+    pub fn memory_mut(&mut self) -> &mut M {
+        &mut self.memory
+    }
+
+    /// Starts recording a [Trace] of the execution (see [`IntcodeVM::trace()`](IntcodeVM::trace))
+    ///
+    /// Does nothing if the trace was already being recorded
+    #[inline]
+    pub fn enable_trace(&mut self) {
+        self.trace.get_or_insert_with(Trace::default);
+    }
+
+    /// Sets a callback called with every instruction right before it is executed
+    ///
+    /// An input instruction waiting for an input is reported again when the execution resumes.
+    /// The hook is not copied when the VM is cloned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::{Arc, Mutex};
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let executed = Arc::new(Mutex::new(Vec::new()));
+    /// let mut vm = IntcodeVM::new([1101, 2, 3, 5, 104, 0, 99]);
+    /// let log = Arc::clone(&executed);
+    /// vm.set_trace_hook(Box::new(move |event| {
+    ///     log.lock().unwrap().push((event.ip, event.opcode, event.modes));
+    /// }));
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(5));
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// assert_eq!(
+    ///     *executed.lock().unwrap(),
+    ///     [(0, 1, [1, 1, 0]), (4, 4, [1, 0, 0]), (6, 99, [0, 0, 0])]
+    /// );
+    /// ```
+    #[inline]
+    pub fn set_trace_hook(&mut self, hook: Box<dyn FnMut(TraceEvent<T>) + Send>) {
+        self.trace_hook.set(hook);
+    }
+
+    /// Returns the [Trace] recorded since [`IntcodeVM::enable_trace()`](IntcodeVM::enable_trace)
+    /// was called, [`None`] if tracing is not enabled
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([1101, 2, 3, 5, 104, 0, 99]);
+    /// vm.enable_trace();
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(5));
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    ///
+    /// let trace = vm.trace().unwrap();
+    /// assert_eq!(trace.instruction_pointers(), &[0, 4, 6]);
+    /// assert_eq!(trace.outputs(), &[5]);
+    /// ```
+    #[inline]
+    pub const fn trace(&self) -> Option<&Trace<T>> {
+        self.trace.as_ref()
+    }
+
+    /// Writes the recorded [Trace] into `writer` (as per [`Trace::write_binary()`](Trace::write_binary))
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`InvalidInput`](io::ErrorKind::InvalidInput)
+    /// if tracing is not enabled, or any error returned by [`Trace::write_binary()`](Trace::write_binary)
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn write_trace_binary<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        self.trace
+            .as_ref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "tracing is not enabled"))?
+            .write_binary(writer)
+    }
+
+    /// Starts counting how many times each memory cell is read from and written to by the
+    /// executed instructions (see [`IntcodeVM::access_profile()`](IntcodeVM::access_profile))
+    ///
+    /// Only the operands are counted, not the fetching of instructions themselves.
+    #[inline]
+    pub fn enable_access_profiling(&mut self) {
+        self.profile_accesses = true;
+    }
+
+    /// Returns the number of `(reads, writes)` of every memory cell accessed since
+    /// [`IntcodeVM::enable_access_profiling()`](IntcodeVM::enable_access_profiling) was called
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([1, 5, 5, 5, 99, 21]);
+    /// vm.enable_access_profiling();
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    ///
+    /// // address 5 was read twice and written once
+    /// assert_eq!(vm.access_profile()[&5], (2, 1));
+    /// assert_eq!(vm.access_profile().len(), 1);
+    /// ```
+    #[inline]
+    pub fn access_profile(&self) -> &BTreeMap<usize, (u64, u64)> {
+        &self.access_profile
+    }
+
+    /// Starts counting how many times each opcode is executed
+    /// (see [`IntcodeVM::opcode_counts()`](IntcodeVM::opcode_counts))
+    #[inline]
+    pub fn enable_profiling(&mut self) {
+        self.profile_opcodes = true;
+    }
+
+    /// Returns the number of times each opcode was executed since
+    /// [`IntcodeVM::enable_profiling()`](IntcodeVM::enable_profiling) was called
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// // counts down from 3
+    /// let mut vm = IntcodeVM::new([1001, 8, -1, 8, 1005, 8, 0, 99, 3]);
+    /// vm.enable_profiling();
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    ///
+    /// assert_eq!(vm.opcode_counts().get(1), 3);
+    /// assert_eq!(vm.opcode_counts().get(5), 3);
+    /// assert_eq!(vm.opcode_counts().get(99), 1);
+    /// assert_eq!(vm.opcode_counts().total(), 7);
+    /// ```
+    #[inline]
+    pub fn opcode_counts(&self) -> &OpcodeCounts {
+        &self.opcode_counts
+    }
+
+    /// Starts recording every input read by the program along with the address it was stored at
+    /// (see [`IntcodeVM::input_log()`](IntcodeVM::input_log))
+    ///
+    /// Does nothing if the inputs were already being recorded
+    #[inline]
+    pub fn enable_input_logging(&mut self) {
+        self.input_log.get_or_insert_with(Vec::new);
+    }
+
+    /// Returns the `(address, value)` of every input read since
+    /// [`IntcodeVM::enable_input_logging()`](IntcodeVM::enable_input_logging) was called
+    /// (empty if input logging is not enabled)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([3, 7, 203, 5, 99]);
+    /// vm.enable_input_logging();
+    ///
+    /// for input in [10, 20] {
+    ///     assert_eq!(vm.run().unwrap(), VMResult::WaitingForInput);
+    ///     vm.set_next_input(input);
+    /// }
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// assert_eq!(vm.input_log(), &[(7, 10), (5, 20)]);
+    /// ```
+    #[inline]
+    pub fn input_log(&self) -> &[(usize, T)] {
+        self.input_log.as_deref().unwrap_or_default()
+    }
+
+    /// Sets what the VM does when it encounters opcode 99 (see [HaltBehavior])
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{vm::HaltBehavior, IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([104, 1, 99]);
+    /// vm.set_halt_behavior(HaltBehavior::Pause);
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(1));
+    /// assert_eq!(vm.run().unwrap(), VMResult::Paused);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Paused);
+    ///
+    /// vm.set_halt_behavior(HaltBehavior::Terminate);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// ```
+    #[inline]
+    pub fn set_halt_behavior(&mut self, behavior: HaltBehavior) {
+        self.halt_behavior = behavior;
+    }
+
+    /// Makes the input instructions take their input from `iter` when the input queue is empty
+    ///
+    /// The inputs are taken from the input queue ([`IntcodeVM::push_input()`](IntcodeVM::push_input)
+    /// and [`IntcodeVM::set_next_input()`](IntcodeVM::set_next_input)) first, then from `iter`,
+    /// then as per [`IntcodeVM::set_input_on_empty()`](IntcodeVM::set_input_on_empty) once `iter` is exhausted.
+    /// Just like the [output callback](IntcodeVM::on_output), `iter` is not copied to the clones of the VM.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// // outputs its input, forever
+    /// let mut vm = IntcodeVM::new([3, 7, 4, 7, 1105, 1, 0, 0]);
+    /// vm.set_input_iter([1, 2].into_iter());
+    ///
+    /// vm.push_input(5);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(5));
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(1));
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(2));
+    /// assert_eq!(vm.run().unwrap(), VMResult::WaitingForInput);
+    /// ```
+    #[inline]
+    pub fn set_input_iter<I: Iterator<Item = T> + Send + 'static>(&mut self, iter: I) {
+        self.input_iter.set(Box::new(iter));
+    }
+
+    /// Sets what the VM does when it encounters opcode 03 while its input queue is empty
+    /// (see [EmptyInputBehavior])
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{vm::EmptyInputBehavior, IntcodeVM, VMResult};
+    /// // outputs its input, forever
+    /// let mut vm = IntcodeVM::new([3, 7, 4, 7, 1105, 1, 0, 0]);
+    /// vm.set_input_on_empty(EmptyInputBehavior::Yield(-1));
+    ///
+    /// vm.push_input(5);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(5));
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(-1));
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(-1));
+    /// ```
+    #[inline]
+    pub fn set_input_on_empty(&mut self, behavior: EmptyInputBehavior<T>) {
+        self.input_on_empty = behavior;
+    }
+
+    /// Address of the next instruction to be executed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([104, 1, 99]);
+    /// assert_eq!(vm.instruction_pointer(), 0);
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(1));
+    /// assert_eq!(vm.instruction_pointer(), 2);
+    /// ```
+    #[inline]
+    pub const fn instruction_pointer(&self) -> usize {
+        self.instruction_ptr
+    }
+
+    /// Moves the instruction pointer to `ptr`, the next call to [`vm.run()`](IntcodeVM::run)
+    /// will start executing from there
+    ///
+    /// `ptr` may be past the end of the memory, the memory reading as 0 there
+    /// (which is not a valid instruction).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([104, 1, 104, 2, 99]);
+    /// vm.set_instruction_pointer(2);
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(2));
+    /// ```
+    #[inline]
+    pub fn set_instruction_pointer(&mut self, ptr: usize) {
+        self.instruction_ptr = ptr;
+    }
+
+    /// Current relative base, used by the arguments in relative mode (2)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([109, 19, 109, -4, 99]);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    ///
+    /// assert_eq!(vm.relative_base(), &15);
+    /// ```
+    #[inline]
+    pub const fn relative_base(&self) -> &T {
+        &self.relative_base_ptr
+    }
+
+    /// Replaces the relative base with `base`, returning the previous one
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// // outputs the value at relative base + 1
+    /// let mut vm = IntcodeVM::new([204, 1, 99, 42]);
+    /// assert_eq!(vm.set_relative_base(2), 0);
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(42));
+    /// ```
+    #[inline]
+    pub fn set_relative_base(&mut self, base: T) -> T {
+        core::mem::replace(&mut self.relative_base_ptr, base)
+    }
+
+    /// Input that will be provided to the next input instruction, [`None`] if the input queue is empty
+    #[inline]
+    pub const fn get_next_input(&self) -> &Option<T> {
+        &self.next_input_value
+    }
+
+    /// Replaces the input at the front of the input queue with `next_input`
+    /// (or adds it if the queue is empty), returning the replaced input
+    #[inline]
+    pub fn set_next_input(&mut self, next_input: T) -> Option<T> {
+        self.next_input_value.replace(next_input)
+    }
+
+    /// Inputs waiting in the input queue, from front to back
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub(crate) fn pending_inputs(&self) -> impl Iterator<Item = &T> {
+        self.next_input_value.iter().chain(&self.input_queue)
+    }
+
+    /// Adds `value` at the back of the input queue
+    ///
+    /// Input instructions take their input from the front of the queue,
+    /// the VM only returns [`VMResult::WaitingForInput`] when it is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// // outputs the sum of its 2 inputs
+    /// let mut vm = IntcodeVM::new([3, 11, 3, 12, 1, 11, 12, 11, 4, 11, 99, 0, 0]);
+    /// vm.push_input(3);
+    /// vm.push_input(4);
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(7));
+    /// ```
+    #[inline]
+    pub fn push_input(&mut self, value: T) {
+        if self.next_input_value.is_none() {
+            self.next_input_value = Some(value);
+        } else {
+            self.input_queue.push_back(value);
+        }
+    }
+
+    /// Adds every value of `values` at the back of the input queue, in order
+    /// (see [`IntcodeVM::push_input()`](IntcodeVM::push_input))
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([3, 11, 3, 12, 1, 11, 12, 11, 4, 11, 99, 0, 0]);
+    /// vm.extend_input([3]);
+    /// assert_eq!(vm.run().unwrap(), VMResult::WaitingForInput);
+    ///
+    /// vm.extend_input([4, 5]);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Output(7));
+    /// assert_eq!(vm.get_next_input(), &Some(5));
+    /// ```
+    pub fn extend_input<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            self.push_input(value);
+        }
+    }
+
+    /// Number of inputs waiting in the input queue
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([3, 0, 99]);
+    /// vm.extend_input([1, 2, 3]);
+    /// assert_eq!(vm.pending_input_len(), 3);
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// assert_eq!(vm.pending_input_len(), 2);
+    /// ```
+    #[inline]
+    pub fn pending_input_len(&self) -> usize {
+        usize::from(self.next_input_value.is_some()) + self.input_queue.len()
+    }
+
+    /// Removes every input waiting in the input queue, returning them from front to back
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([3, 0, 99]);
+    /// vm.extend_input([1, 2, 3]);
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// assert_eq!(vm.take_pending_input(), [2, 3]);
+    /// assert_eq!(vm.pending_input_len(), 0);
+    /// ```
+    pub fn take_pending_input(&mut self) -> Vec<T> {
+        let mut inputs: Vec<T> = self.next_input_value.take().into_iter().collect();
+        inputs.extend(self.input_queue.drain(..));
+        inputs
+    }
+
+    /// Registers a callback called with every output, the execution then continues
+    /// instead of returning [`VMResult::Output`]
+    ///
+    /// The callback takes precedence over [buffered output](IntcodeVM::set_buffer_output):
+    /// while it is registered, outputs are only given to it and never buffered.
+    /// The callback is not copied when the VM is cloned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::{Arc, Mutex};
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let outputs = Arc::new(Mutex::new(Vec::new()));
+    /// let mut vm = IntcodeVM::new([104, 1, 104, 2, 104, 3, 99]);
+    /// let sink = Arc::clone(&outputs);
+    /// vm.on_output(move |out| sink.lock().unwrap().push(out));
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// assert_eq!(*outputs.lock().unwrap(), [1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn on_output<F: FnMut(T) + Send + 'static>(&mut self, f: F) {
+        self.output_hook.set(Box::new(f));
+    }
+
+    /// Makes the output instructions write their value to the cell at `address` and continue the execution,
+    /// instead of returning [`VMResult::Output`]
+    ///
+    /// The values written this way are not outputs: they are not [buffered](IntcodeVM::set_buffer_output)
+    /// nor passed to the [output callback](IntcodeVM::on_output). They are writes like any other, subject
+    /// to the [memory limit](IntcodeVM::set_max_memory): a [watchpoint](IntcodeVM::add_watchpoint)
+    /// on `address` stops the execution after each of them.
+    ///
+    /// The outputs are returned normally by default (see [`IntcodeVM::unmap_output()`](IntcodeVM::unmap_output)).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([104, 1, 104, 2, 99]);
+    /// vm.map_output_to_memory(100);
+    /// vm.add_watchpoint(100);
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::WatchpointHit { address: 100, old: 0, new: 1 });
+    /// assert_eq!(vm.run().unwrap(), VMResult::WatchpointHit { address: 100, old: 1, new: 2 });
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// ```
+    #[inline]
+    pub fn map_output_to_memory(&mut self, address: usize) {
+        self.output_address = Some(address);
+    }
+
+    /// Makes the output instructions return [`VMResult::Output`] again
+    /// after a call to [`IntcodeVM::map_output_to_memory()`](IntcodeVM::map_output_to_memory)
+    #[inline]
+    pub fn unmap_output(&mut self) {
+        self.output_address = None;
+    }
+
+    /// When `enabled`, output instructions add their output to a buffer
+    /// (see [`IntcodeVM::drain_output()`](IntcodeVM::drain_output)) and the execution continues,
+    /// instead of returning [`VMResult::Output`]
+    ///
+    /// Disabling it keeps the outputs already buffered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let mut vm = IntcodeVM::new([104, 1, 104, 2, 104, 3, 99]);
+    /// vm.set_buffer_output(true);
+    ///
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// assert_eq!(vm.drain_output(), [1, 2, 3]);
+    /// assert!(vm.drain_output().is_empty());
+    /// ```
+    #[inline]
+    pub fn set_buffer_output(&mut self, enabled: bool) {
+        self.buffer_output = enabled;
+    }
+
+    /// Takes the outputs buffered since the last call
+    /// (see [`IntcodeVM::set_buffer_output()`](IntcodeVM::set_buffer_output))
+    #[inline]
+    pub fn drain_output(&mut self) -> Vec<T> {
+        core::mem::take(&mut self.output_buffer)
+    }
+
+    /// Outputs buffered since the last call to [`IntcodeVM::drain_output()`](IntcodeVM::drain_output),
+    /// without removing them from the buffer
     ///
-    /// # Examples
+    /// # Example
     ///
     /// ```
     /// # use intcode_vm::{IntcodeVM, VMResult};
-    /// let mut vm = IntcodeVM::new([1, 0, 0, 3, 99]);
-    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
-    /// ```
+    /// let mut vm = IntcodeVM::new([104, 1, 104, 2, 3, 0, 104, 3, 99]);
+    /// vm.set_buffer_output(true);
     ///
-    /// Will return a [VMError] if a problem occurred, such as an unrecognized op code
-    /// ```
-    /// # use intcode_vm::IntcodeVM;
-    /// let mut vm = IntcodeVM::new([15]); // 15 is not a valid op code
-    /// assert!(vm.run().is_err());
+    /// assert_eq!(vm.run().unwrap(), VMResult::WaitingForInput);
+    /// assert_eq!(vm.peek_output(), &[1, 2]);
+    ///
+    /// vm.push_input(0);
+    /// assert_eq!(vm.run().unwrap(), VMResult::Halted);
+    /// assert_eq!(vm.peek_output(), &[1, 2, 3]);
     /// ```
     #[inline]
-    pub fn run(&mut self) -> error::Result<VMResult<T>, T> {
-        loop {
-            let instruction = instr::Instruction::from_current_instr_ptr(self)?;
-            let instruction_width = instruction.instruction_width();
-            match instruction {
-                instr::Instruction::Add(arg1, arg2, dest) => {
-                    let arg1_val = arg1.resolve_value(self)?;
-                    let arg2_val = arg2.resolve_value(self)?;
-                    let destination_addr = dest.resolve_address(self)?;
-
-                    let result = arg1_val.clone() + arg2_val.clone();
-                    self.memory.set(destination_addr, result);
-                    self.increment_instr_ptr_by(instruction_width);
-                }
-
-                instr::Instruction::Mul(arg1, arg2, dest) => {
-                    let arg1_val = arg1.resolve_value(self)?;
-                    let arg2_val = arg2.resolve_value(self)?;
-                    let destination_addr = dest.resolve_address(self)?;
-
-                    let result = arg1_val.clone() * arg2_val.clone();
-                    self.memory.set(destination_addr, result);
-                    self.increment_instr_ptr_by(instruction_width);
-                }
-
-                instr::Instruction::ReadInput(dest) => {
-                    let destination_addr = dest.resolve_address(self)?;
-                    if let Some(input) = self.next_input_value.take() {
-                        self.memory.set(destination_addr, input);
-                        self.increment_instr_ptr_by(instruction_width);
-                    } else {
-                        return Ok(VMResult::WaitingForInput);
-                    }
-                }
-
-                instr::Instruction::WriteOutput(arg) => {
-                    let res = arg.resolve_value(self)?.clone();
-                    self.increment_instr_ptr_by(instruction_width);
-                    return Ok(VMResult::Output(res));
-                }
-
-                instr::Instruction::JmpIfTrue(arg, target) => {
-                    if !arg.resolve_value(self)?.is_zero() {
-                        let target_value = target.resolve_value(self)?;
-                        let new_instr_ptr = target_value
-                            .to_usize()
-                            .ok_or_else(|| VMError::CannotCastToUsize(target_value.clone()))?;
-
-                        self.instruction_ptr = new_instr_ptr;
-                    } else {
-                        self.increment_instr_ptr_by(instruction_width);
-                    }
-                }
-
-                instr::Instruction::JmpIfFalse(arg, target) => {
-                    if arg.resolve_value(self)?.is_zero() {
-                        let target_value = target.resolve_value(self)?;
-                        let new_instr_ptr = target_value
-                            .to_usize()
-                            .ok_or_else(|| VMError::CannotCastToUsize(target_value.clone()))?;
-
-                        self.instruction_ptr = new_instr_ptr;
-                    } else {
-                        self.increment_instr_ptr_by(instruction_width);
-                    }
-                }
-
-                instr::Instruction::LessThan(arg1, arg2, result) => {
-                    let arg1_val = arg1.resolve_value(self)?;
-                    let arg2_val = arg2.resolve_value(self)?;
-                    let dest = result.resolve_address(self)?;
-                    if arg1_val < arg2_val {
-                        self.memory.set(dest, T::one());
-                    } else {
-                        self.memory.set(dest, T::zero());
-                    }
+    pub fn peek_output(&self) -> &[T] {
+        &self.output_buffer
+    }
 
-                    self.increment_instr_ptr_by(instruction_width);
-                }
+    /// Moves the instruction pointer to `target` for the jump instruction at `from`
+    #[inline]
+    fn jump_to(&mut self, from: usize, target: usize) -> error::Result<(), T> {
+        if let Some(starts) = &self.instruction_starts {
+            if !starts.contains(&target) {
+                return Err(VMError::MisalignedJump { from, target });
+            }
+        }
 
-                instr::Instruction::Equals(arg1, arg2, result) => {
-                    let arg1_val = arg1.resolve_value(self)?;
-                    let arg2_val = arg2.resolve_value(self)?;
-                    let dest = result.resolve_address(self)?;
-                    if arg1_val == arg2_val {
-                        self.memory.set(dest, T::one());
-                    } else {
-                        self.memory.set(dest, T::zero());
-                    }
+        self.instruction_ptr = target;
+        Ok(())
+    }
 
-                    self.increment_instr_ptr_by(instruction_width);
-                }
+    /// Writes into memory on behalf of an instruction
+    #[inline]
+    fn write_memory(&mut self, address: usize, value: T) -> error::Result<(), T> {
+        self.check_memory_limit(address)?;
+        if self.profile_accesses {
+            self.access_profile.entry(address).or_default().1 += 1;
+        }
 
-                instr::Instruction::AddRelativeBase(arg) => {
-                    let arg_val = arg.resolve_value(self)?;
-                    self.relative_base_ptr = self.relative_base_ptr.clone() + arg_val.clone();
+        if self.watchpoints.contains(&address) {
+            let old = self.memory.get(address).clone();
+            self.watchpoint_hit = Some((address, old, value.clone()));
+        }
 
-                    self.increment_instr_ptr_by(instruction_width);
-                }
+        if let Some(history) = &mut self.history {
+            history.current_write = Some((address, self.memory.get(address).clone()));
+        }
 
-                instr::Instruction::Halt => return Ok(VMResult::Halted),
-            }
+        self.memory.set(address, value);
+        if let Some(peak) = &mut self.peak_memory_len {
+            *peak = (*peak).max(self.memory.len());
         }
-    }
 
-    /// Returns the internal [Memory] of the VM
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// # use intcode_vm::IntcodeVM;
-    /// let vm = IntcodeVM::from([1, 0, 0, 3, 99]);
-    /// let memory = vm.into_memory();
-    ///
-    /// assert!(memory.memory_starts_with([1, 0, 0, 3, 99].iter()));
-    /// ```
-    #[inline]
-    pub fn into_memory(self) -> Memory<T> {
-        self.memory
+        Ok(())
     }
 
+    /// Checks `address` can be written to without going over the memory limit
     #[inline]
-    pub const fn get_next_input(&self) -> &Option<T> {
-        &self.next_input_value
+    fn check_memory_limit(&self, address: usize) -> error::Result<(), T> {
+        match self.max_memory {
+            Some(limit) if address >= limit => Err(VMError::MemoryLimitExceeded { address, limit }),
+            _ => Ok(()),
+        }
     }
 
+    /// Checks `address` can be read from when the memory is strict
     #[inline]
-    pub fn set_next_input(&mut self, next_input: T) -> Option<T> {
-        self.next_input_value.replace(next_input)
+    fn check_in_bounds(&self, address: usize) -> error::Result<(), T> {
+        if address < self.memory.len() {
+            Ok(())
+        } else {
+            Err(VMError::AddressOutOfBounds(address))
+        }
     }
 
     #[inline]
     fn increment_instr_ptr_by(&mut self, incr: usize) {
         self.instruction_ptr += incr;
     }
+}
 
-    #[inline]
-    fn get_at_instr_ptr(&self, offset: usize) -> &T {
-        self.memory.get(self.instruction_ptr + offset)
+/// Compares the execution state of the VMs: their memory, instruction pointer, relative base and input queue
+///
+/// The options (such as breakpoints or limits) and the statistics (such as the trace or the number of
+/// executed instructions) are not compared: two equal VMs with the same options behave identically from then on.
+///
+/// # Example
+///
+/// ```
+/// # use std::collections::HashSet;
+/// # use intcode_vm::{IntcodeVM, VMResult};
+/// // decrements a counter (at 14) from 3 to 0, then starts over
+/// let mut vm = IntcodeVM::new([1101, 0, 3, 14, 1001, 14, -1, 14, 1006, 14, 0, 1105, 1, 4]);
+/// let mut seen = HashSet::new();
+/// while seen.insert(vm.clone()) {
+///     assert_eq!(vm.step().unwrap(), VMResult::Continue);
+/// }
+///
+/// // back at the start with the counter at 0, 9 instructions later
+/// assert_eq!(vm.instruction_pointer(), 0);
+/// assert_eq!(seen.len(), 9);
+/// ```
+impl<T, M> PartialEq for IntcodeVM<T, M>
+where
+    T: Integer + Clone + ToPrimitive,
+    M: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.instruction_ptr == other.instruction_ptr
+            && self.relative_base_ptr == other.relative_base_ptr
+            && self.next_input_value == other.next_input_value
+            && self.input_queue == other.input_queue
+            && self.resumed_breakpoint == other.resumed_breakpoint
+            && self.memory == other.memory
     }
+}
 
-    #[inline]
-    fn get_3_after_intr_ptr(&self) -> (&T, &T, &T) {
-        (
-            self.get_at_instr_ptr(1),
-            self.get_at_instr_ptr(2),
-            self.get_at_instr_ptr(3),
-        )
-    }
+impl<T, M> Eq for IntcodeVM<T, M>
+where
+    T: Integer + Clone + ToPrimitive + Eq,
+    M: Eq,
+{
+}
 
-    #[inline]
-    fn get_2_after_intr_ptr(&self) -> (&T, &T) {
-        (self.get_at_instr_ptr(1), self.get_at_instr_ptr(2))
+/// Hashes the execution state of the VM, consistently with [PartialEq]
+impl<T, M> Hash for IntcodeVM<T, M>
+where
+    T: Integer + Clone + ToPrimitive + Hash,
+    M: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.instruction_ptr.hash(state);
+        self.relative_base_ptr.hash(state);
+        self.next_input_value.hash(state);
+        self.input_queue.hash(state);
+        self.resumed_breakpoint.hash(state);
+        self.memory.hash(state);
     }
 }
 
@@ -245,6 +2715,55 @@ where
     }
 }
 
+impl<T> IntcodeVM<T>
+where
+    T: Integer + Clone + ToPrimitive + FromStr,
+{
+    /// Loads the program in the file at `path` (as per [`Memory::from_file()`](Memory::from_file))
+    /// then build a VM from it
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use intcode_vm::IntcodeVM;
+    /// let mut vm: IntcodeVM<i64> = IntcodeVM::from_file("input/2019/day5.txt").unwrap();
+    /// ```
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, FromFileError<<T as FromStr>::Err>> {
+        Memory::from_file(path).map(Self::new)
+    }
+
+    /// Parses every non-empty line of `s` as a program (as per [`IntcodeVM::from_str()`](IntcodeVM::from_str)),
+    /// yielding a VM for each of them
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use intcode_vm::{IntcodeVM, VMResult};
+    /// let programs = "1,0,0,3,99\n\n104,42,99\n1,x,99\n";
+    /// let mut vms = IntcodeVM::<i32>::parse_many(programs);
+    ///
+    /// assert_eq!(vms.next().unwrap().unwrap().run().unwrap(), VMResult::Halted);
+    /// assert_eq!(vms.next().unwrap().unwrap().run().unwrap(), VMResult::Output(42));
+    /// assert_eq!(vms.next().unwrap().unwrap_err().line, 4);
+    /// assert!(vms.next().is_none());
+    /// ```
+    pub fn parse_many(
+        s: &str,
+    ) -> impl Iterator<Item = Result<Self, ParseError<<T as FromStr>::Err>>> + '_ {
+        s.lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(line, program)| {
+                program
+                    .parse()
+                    .map_err(|source| ParseError { line, source })
+            })
+    }
+}
+
 impl<T> FromStr for IntcodeVM<T>
 where
     T: Integer + Clone + ToPrimitive + FromStr,
@@ -268,25 +2787,52 @@ where
     }
 }
 
-mod instr {
+pub(crate) mod instr {
+    use core::fmt;
+
+    use alloc::{
+        collections::{BTreeMap, BTreeSet},
+        vec,
+    };
+
     use num::{Integer, ToPrimitive};
 
     use crate::{
+        disasm::DecodedInstruction,
         error::{self, VMError},
+        memory::{Memory, MemoryRead, MemoryStore},
         IntcodeVM,
     };
 
+    /// Converts `value` to a memory address
+    ///
+    /// Returns [`VMError::CannotCastToUsize`] if `value` is negative
+    /// and [`VMError::AddressTooLarge`] if it is bigger than [`usize::MAX`]
+    #[inline]
+    pub(crate) fn to_address<T>(value: T) -> error::Result<usize, T>
+    where
+        T: Integer + Clone + ToPrimitive,
+    {
+        match value.to_usize() {
+            Some(address) => Ok(address),
+            None if value < T::zero() => Err(VMError::CannotCastToUsize(value)),
+            None => Err(VMError::AddressTooLarge(value)),
+        }
+    }
+
     #[derive(Debug, Clone, Copy)]
     enum ArgMode {
-        Positional,
-        Immediate,
-        Relative,
+        Positional = 0,
+        Immediate = 1,
+        Relative = 2,
     }
 
     #[derive(Debug, Clone)]
-    pub(super) struct ArgInfo<'t, T> {
+    pub(crate) struct ArgInfo<'t, T> {
         opcode: u16,
         arg_num: u8,
+        /// Address of the instruction the argument belongs to
+        address: usize,
         mode: ArgMode,
         value: &'t T,
     }
@@ -296,60 +2842,193 @@ mod instr {
         T: Integer + Clone + ToPrimitive,
     {
         #[inline]
-        pub(super) fn resolve_value(&self, vm: &'vm IntcodeVM<T>) -> error::Result<&'vm T, T> {
+        pub(crate) fn resolve_value<M: MemoryStore<T>>(
+            &self,
+            vm: &'vm IntcodeVM<T, M>,
+        ) -> error::Result<&'vm T, T> {
+            self.resolve_value_in(&vm.memory, &vm.relative_base_ptr)
+        }
+
+        #[inline]
+        pub(crate) fn resolve_address<M>(
+            &self,
+            vm: &'vm IntcodeVM<T, M>,
+        ) -> error::Result<usize, T> {
+            self.resolve_address_with(&vm.relative_base_ptr)
+        }
+
+        /// Resolves the value of the argument in `memory` with the relative base `relative_base`
+        #[inline]
+        pub(crate) fn resolve_value_in<M: MemoryRead<T>>(
+            &self,
+            memory: &'vm M,
+            relative_base: &T,
+        ) -> error::Result<&'vm T, T> {
             match self.mode {
                 ArgMode::Immediate => Ok(self.value),
-                ArgMode::Positional => Ok(vm.memory.get(
-                    self.value
-                        .to_usize()
-                        .ok_or_else(|| VMError::CannotCastToUsize(self.value.clone()))?,
-                )),
-                ArgMode::Relative => {
-                    let real_address = self.value.clone() + vm.relative_base_ptr.clone();
-                    Ok(vm.memory.get(
-                        real_address
-                            .to_usize()
-                            .ok_or(VMError::CannotCastToUsize(real_address))?,
-                    ))
+                ArgMode::Positional | ArgMode::Relative => {
+                    Ok(memory.get(self.resolve_address_with(relative_base)?))
                 }
             }
         }
 
+        /// Resolves the address pointed to by the argument with the relative base `relative_base`
         #[inline]
-        pub(super) fn resolve_address(&self, vm: &'vm IntcodeVM<T>) -> error::Result<usize, T> {
+        pub(crate) fn resolve_address_with(&self, relative_base: &T) -> error::Result<usize, T> {
             match self.mode {
                 ArgMode::Immediate => Err(VMError::ArgModeCannotBeImmediate {
                     opcode: self.opcode,
                     arg_num: self.arg_num,
+                    address: self.address,
                 }),
-                ArgMode::Positional => self
-                    .value
-                    .to_usize()
-                    .ok_or_else(|| VMError::CannotCastToUsize(self.value.clone())),
-                ArgMode::Relative => {
-                    let real_address = self.value.clone() + vm.relative_base_ptr.clone();
-                    real_address
-                        .to_usize()
-                        .ok_or(VMError::CannotCastToUsize(real_address))
-                }
+                ArgMode::Positional => to_address(self.value.clone()),
+                ArgMode::Relative => to_address(self.value.clone() + relative_base.clone()),
+            }
+        }
+    }
+
+    impl<'vm, T> ArgInfo<'vm, T>
+    where
+        T: Integer + Clone + ToPrimitive,
+    {
+        /// Address of the cell read by [`ArgInfo::resolve_value()`], [`None`] in immediate mode
+        #[inline]
+        fn read_address<M>(&self, vm: &'vm IntcodeVM<T, M>) -> error::Result<Option<usize>, T> {
+            match self.mode {
+                ArgMode::Immediate => Ok(None),
+                ArgMode::Positional | ArgMode::Relative => self.resolve_address(vm).map(Some),
+            }
+        }
+    }
+
+    impl<'t, T> ArgInfo<'t, T>
+    where
+        T: fmt::Display,
+    {
+        /// Writes the argument as an address to write to (`&5` positional, `&~5` relative)
+        fn fmt_destination(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self.mode {
+                ArgMode::Positional => write!(f, "&{}", self.value),
+                ArgMode::Immediate => write!(f, "#{}", self.value),
+                ArgMode::Relative => write!(f, "&~{}", self.value),
+            }
+        }
+    }
+
+    /// `[5]` positional, `#5` immediate, `[~5]` relative
+    impl<'t, T> fmt::Display for ArgInfo<'t, T>
+    where
+        T: fmt::Display,
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self.mode {
+                ArgMode::Positional => write!(f, "[{}]", self.value),
+                ArgMode::Immediate => write!(f, "#{}", self.value),
+                ArgMode::Relative => write!(f, "[~{}]", self.value),
             }
         }
     }
 
-    impl<'t, T> From<(u16, &'t T, ArgMode, u8)> for ArgInfo<'t, T> {
+    impl<'t, T> From<(u16, &'t T, ArgMode, u8, usize)> for ArgInfo<'t, T> {
         #[inline]
-        fn from(value: (u16, &'t T, ArgMode, u8)) -> Self {
+        fn from(value: (u16, &'t T, ArgMode, u8, usize)) -> Self {
             Self {
                 opcode: value.0,
                 arg_num: value.3,
+                address: value.4,
                 mode: value.2,
                 value: value.1,
             }
         }
     }
 
+    /// Position of an instruction in memory
+    struct InstrAt<'t, M> {
+        memory: &'t M,
+        address: usize,
+    }
+
+    impl<'t, M> InstrAt<'t, M> {
+        #[inline]
+        fn get<T>(&self, offset: usize) -> &'t T
+        where
+            M: MemoryRead<T>,
+        {
+            self.memory.get(self.address + offset)
+        }
+
+        #[inline]
+        fn get_3_after<T>(&self) -> (&'t T, &'t T, &'t T)
+        where
+            M: MemoryRead<T>,
+        {
+            (self.get(1), self.get(2), self.get(3))
+        }
+
+        #[inline]
+        fn get_2_after<T>(&self) -> (&'t T, &'t T)
+        where
+            M: MemoryRead<T>,
+        {
+            (self.get(1), self.get(2))
+        }
+    }
+
+    /// Decodes every instruction statically reachable from `start`, following the execution flow
+    /// as far as possible without running the program
+    ///
+    /// Conditional jumps are followed both ways (unless their condition is an immediate value),
+    /// jumps to a positional or relative target cannot be followed.
+    /// The exploration stops at halt instructions and at cells that cannot be decoded.
+    pub(crate) fn reachable_instructions<T>(
+        memory: &Memory<T>,
+        start: usize,
+    ) -> BTreeMap<usize, error::Result<Instruction<'_, T>, T>>
+    where
+        T: Integer + Clone + ToPrimitive,
+    {
+        let mut reachable = BTreeMap::new();
+        let mut to_visit = vec![start];
+        while let Some(address) = to_visit.pop() {
+            if reachable.contains_key(&address) {
+                continue;
+            }
+
+            let instruction = Instruction::decode(memory, address);
+            if let Ok(instruction) = &instruction {
+                to_visit.extend(instruction.static_successors(address).into_iter().flatten());
+            }
+
+            reachable.insert(address, instruction);
+        }
+
+        reachable
+    }
+
+    /// Addresses at which an instruction starts when decoding `memory` linearly from address 0
+    ///
+    /// Cells that cannot be decoded are skipped one by one.
+    pub(crate) fn linear_instruction_starts<T>(memory: &Memory<T>) -> BTreeSet<usize>
+    where
+        T: Integer + Clone + ToPrimitive,
+    {
+        let mut starts = BTreeSet::new();
+        let mut address = 0;
+        while address < memory.len() {
+            match Instruction::decode(memory, address) {
+                Ok(instruction) => {
+                    starts.insert(address);
+                    address += instruction.instruction_width();
+                }
+                Err(_) => address += 1,
+            }
+        }
+
+        starts
+    }
+
     #[derive(Debug, Clone)]
-    pub(super) enum Instruction<'t, T> {
+    pub(crate) enum Instruction<'t, T> {
         Add(ArgInfo<'t, T>, ArgInfo<'t, T>, ArgInfo<'t, T>),
         Mul(ArgInfo<'t, T>, ArgInfo<'t, T>, ArgInfo<'t, T>),
         ReadInput(ArgInfo<'t, T>),
@@ -362,35 +3041,108 @@ mod instr {
         Halt,
     }
 
+    /// Human readable form of the instruction, such as `ADD [4] #3 -> &5`
+    impl<'t, T> fmt::Display for Instruction<'t, T>
+    where
+        T: fmt::Display,
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let (mnemonic, arg1, arg2, dest) = match self {
+                Self::Add(arg1, arg2, dest) => ("ADD", arg1, arg2, dest),
+                Self::Mul(arg1, arg2, dest) => ("MUL", arg1, arg2, dest),
+                Self::LessThan(arg1, arg2, dest) => ("LT", arg1, arg2, dest),
+                Self::Equals(arg1, arg2, dest) => ("EQ", arg1, arg2, dest),
+                Self::ReadInput(dest) => {
+                    write!(f, "IN -> ")?;
+                    return dest.fmt_destination(f);
+                }
+                Self::WriteOutput(arg) => return write!(f, "OUT {arg}"),
+                Self::JmpIfTrue(arg, target) => return write!(f, "JNZ {arg} {target}"),
+                Self::JmpIfFalse(arg, target) => return write!(f, "JZ {arg} {target}"),
+                Self::AddRelativeBase(arg) => return write!(f, "ARB {arg}"),
+                Self::Halt => return write!(f, "HALT"),
+            };
+
+            write!(f, "{mnemonic} {arg1} {arg2} -> ")?;
+            dest.fmt_destination(f)
+        }
+    }
+
     impl<'t, T> Instruction<'t, T>
     where
         T: Integer + Clone + ToPrimitive + 't,
     {
         #[inline]
-        pub(super) fn from_current_instr_ptr(vm: &'t IntcodeVM<T>) -> error::Result<Self, T> {
-            let instr = vm.get_at_instr_ptr(0);
-            let op = instr
-                .to_u16()
-                .ok_or_else(|| VMError::CannotCastToU16(instr.clone()))?;
+        pub(crate) fn from_current_instr_ptr<M: MemoryStore<T>>(
+            vm: &'t IntcodeVM<T, M>,
+        ) -> error::Result<Self, T> {
+            Self::decode(&vm.memory, vm.instruction_ptr)
+        }
+
+        /// Decodes the instruction at `address` in `memory`
+        pub(crate) fn decode<M: MemoryRead<T>>(
+            memory: &'t M,
+            address: usize,
+        ) -> error::Result<Self, T> {
+            let at = InstrAt { memory, address };
+            let instr = at.get(0);
+            let op = instr.to_u16().ok_or_else(|| VMError::CannotCastToU16 {
+                value: instr.clone(),
+                address,
+            })?;
 
-            let (arg1_mode, arg2_mode, arg3_mode) = Self::get_3_arg_modes(op)?;
+            let (arg1_mode, arg2_mode, arg3_mode) = Self::get_3_arg_modes(op, address)?;
             match op % 100 {
-                1 => Self::create_add(vm, arg1_mode, arg2_mode, arg3_mode, op),
-                2 => Self::create_mul(vm, arg1_mode, arg2_mode, arg3_mode, op),
-                3 => Self::create_read_input(vm, arg1_mode, arg2_mode, arg3_mode, op),
-                4 => Self::create_write_output(vm, arg1_mode, arg2_mode, arg3_mode, op),
-                5 => Self::create_jmp_if_true(vm, arg1_mode, arg2_mode, arg3_mode, op),
-                6 => Self::create_jmp_if_false(vm, arg1_mode, arg2_mode, arg3_mode, op),
-                7 => Self::create_less_than(vm, arg1_mode, arg2_mode, arg3_mode, op),
-                8 => Self::create_equals(vm, arg1_mode, arg2_mode, arg3_mode, op),
-                9 => Self::create_add_relative_base(vm, arg1_mode, arg2_mode, arg3_mode, op),
+                1 => Self::create_add(at, arg1_mode, arg2_mode, arg3_mode, op),
+                2 => Self::create_mul(at, arg1_mode, arg2_mode, arg3_mode, op),
+                3 => Self::create_read_input(at, arg1_mode, arg2_mode, arg3_mode, op),
+                4 => Self::create_write_output(at, arg1_mode, arg2_mode, arg3_mode, op),
+                5 => Self::create_jmp_if_true(at, arg1_mode, arg2_mode, arg3_mode, op),
+                6 => Self::create_jmp_if_false(at, arg1_mode, arg2_mode, arg3_mode, op),
+                7 => Self::create_less_than(at, arg1_mode, arg2_mode, arg3_mode, op),
+                8 => Self::create_equals(at, arg1_mode, arg2_mode, arg3_mode, op),
+                9 => Self::create_add_relative_base(at, arg1_mode, arg2_mode, arg3_mode, op),
                 99 => Ok(Self::Halt),
-                other => Err(VMError::UnknownInstruction(other)),
+                other => Err(VMError::UnknownInstruction {
+                    opcode: other,
+                    address,
+                }),
+            }
+        }
+
+        /// Owned copy of the instruction, decoded at `address`
+        pub(crate) fn to_decoded(&self, address: usize) -> DecodedInstruction<T> {
+            DecodedInstruction {
+                address,
+                opcode: self.opcode(),
+                operands: self
+                    .args()
+                    .into_iter()
+                    .flatten()
+                    .map(|arg| (arg.mode as u8, arg.value.clone()))
+                    .collect(),
+            }
+        }
+
+        /// Opcode of the instruction, without the argument modes
+        #[inline]
+        pub(crate) const fn opcode(&self) -> u16 {
+            match self {
+                Self::Add(_, _, _) => 1,
+                Self::Mul(_, _, _) => 2,
+                Self::ReadInput(_) => 3,
+                Self::WriteOutput(_) => 4,
+                Self::JmpIfTrue(_, _) => 5,
+                Self::JmpIfFalse(_, _) => 6,
+                Self::LessThan(_, _, _) => 7,
+                Self::Equals(_, _, _) => 8,
+                Self::AddRelativeBase(_) => 9,
+                Self::Halt => 99,
             }
         }
 
         #[inline]
-        pub(super) const fn instruction_width(&self) -> usize {
+        pub(crate) const fn instruction_width(&self) -> usize {
             match self {
                 Self::Add(_, _, _) => 4,
                 Self::Mul(_, _, _) => 4,
@@ -405,138 +3157,298 @@ mod instr {
             }
         }
 
+        /// Addresses of the memory cells this instruction would read if executed now
+        #[inline]
+        pub(crate) fn read_addresses<M: MemoryStore<T>>(
+            &self,
+            vm: &'t IntcodeVM<T, M>,
+        ) -> error::Result<[Option<usize>; 2], T> {
+            Ok(match self {
+                Self::Add(arg1, arg2, _)
+                | Self::Mul(arg1, arg2, _)
+                | Self::LessThan(arg1, arg2, _)
+                | Self::Equals(arg1, arg2, _) => [arg1.read_address(vm)?, arg2.read_address(vm)?],
+                Self::WriteOutput(arg) | Self::AddRelativeBase(arg) => {
+                    [arg.read_address(vm)?, None]
+                }
+                Self::JmpIfTrue(arg, target) => {
+                    let jumps = !arg.resolve_value(vm)?.is_zero();
+                    [
+                        arg.read_address(vm)?,
+                        jumps
+                            .then(|| target.read_address(vm))
+                            .transpose()?
+                            .flatten(),
+                    ]
+                }
+                Self::JmpIfFalse(arg, target) => {
+                    let jumps = arg.resolve_value(vm)?.is_zero();
+                    [
+                        arg.read_address(vm)?,
+                        jumps
+                            .then(|| target.read_address(vm))
+                            .transpose()?
+                            .flatten(),
+                    ]
+                }
+                Self::ReadInput(_) | Self::Halt => [None, None],
+            })
+        }
+
+        /// Emits the [log] message of the instruction about to be executed at `ip`:
+        /// the values read by its arguments and `@address` for its destination, `?` for the ones that cannot be resolved
+        #[cfg(feature = "log")]
+        pub(crate) fn log_execution<M: MemoryStore<T>>(&self, ip: usize, vm: &'t IntcodeVM<T, M>) {
+            use core::fmt::Write;
+
+            use alloc::string::String;
+
+            if !log::log_enabled!(log::Level::Trace) {
+                return;
+            }
+
+            let destination = match self {
+                Self::Add(_, _, _)
+                | Self::Mul(_, _, _)
+                | Self::LessThan(_, _, _)
+                | Self::Equals(_, _, _) => Some(2),
+                Self::ReadInput(_) => Some(0),
+                _ => None,
+            };
+            let mut operands = String::new();
+            for (index, arg) in self.args().into_iter().flatten().enumerate() {
+                if index > 0 {
+                    operands.push_str(", ");
+                }
+                let _ = if destination == Some(index) {
+                    match arg.resolve_address(vm) {
+                        Ok(address) => write!(operands, "@{address}"),
+                        Err(_) => operands.write_str("?"),
+                    }
+                } else {
+                    match arg.resolve_value(vm) {
+                        Ok(value) => write!(operands, "{}", super::LogValue(value)),
+                        Err(_) => operands.write_str("?"),
+                    }
+                };
+            }
+
+            log::trace!("ip={ip} opcode={} operands=[{operands}]", self.opcode());
+        }
+
+        /// Every (valid) address referenced by the arguments of this instruction
+        #[inline]
+        pub(crate) fn operand_addresses<M>(
+            &self,
+            vm: &'t IntcodeVM<T, M>,
+        ) -> impl Iterator<Item = usize> + '_ {
+            self.args()
+                .into_iter()
+                .flatten()
+                .filter_map(move |arg| arg.read_address(vm).ok().flatten())
+        }
+
+        /// Addresses of the instructions that may be executed after this one (at `address`)
+        /// when they can be known without running the program
+        #[inline]
+        fn static_successors(&self, address: usize) -> [Option<usize>; 2] {
+            let next = Some(address + self.instruction_width());
+            match self {
+                Self::Halt => [None, None],
+                Self::JmpIfTrue(arg, target) | Self::JmpIfFalse(arg, target) => {
+                    let jumps_if_zero = matches!(self, Self::JmpIfFalse(_, _));
+                    let target = match target.mode {
+                        ArgMode::Immediate => target.value.to_usize(),
+                        ArgMode::Positional | ArgMode::Relative => None,
+                    };
+
+                    match arg.mode {
+                        ArgMode::Immediate if arg.value.is_zero() == jumps_if_zero => {
+                            [target, None]
+                        }
+                        ArgMode::Immediate => [next, None],
+                        ArgMode::Positional | ArgMode::Relative => [next, target],
+                    }
+                }
+                _ => [next, None],
+            }
+        }
+
+        /// Addresses referenced by the positional arguments of this instruction
+        #[inline]
+        pub(crate) fn positional_operands(&self) -> impl Iterator<Item = usize> + '_ {
+            self.args()
+                .into_iter()
+                .flatten()
+                .filter(|arg| matches!(arg.mode, ArgMode::Positional))
+                .filter_map(|arg| arg.value.to_usize())
+        }
+
+        #[inline]
+        fn args(&self) -> [Option<&ArgInfo<'t, T>>; 3] {
+            match self {
+                Self::Add(arg1, arg2, arg3)
+                | Self::Mul(arg1, arg2, arg3)
+                | Self::LessThan(arg1, arg2, arg3)
+                | Self::Equals(arg1, arg2, arg3) => [Some(arg1), Some(arg2), Some(arg3)],
+                Self::JmpIfTrue(arg1, arg2) | Self::JmpIfFalse(arg1, arg2) => {
+                    [Some(arg1), Some(arg2), None]
+                }
+                Self::ReadInput(arg) | Self::WriteOutput(arg) | Self::AddRelativeBase(arg) => {
+                    [Some(arg), None, None]
+                }
+                Self::Halt => [None, None, None],
+            }
+        }
+
         #[inline]
-        fn create_add(
-            vm: &'t IntcodeVM<T>,
+        fn create_add<M: MemoryRead<T>>(
+            at: InstrAt<'t, M>,
             arg1_mode: ArgMode,
             arg2_mode: ArgMode,
             arg3_mode: ArgMode,
             opcode: u16,
         ) -> error::Result<Self, T> {
-            let (arg1, arg2, dest) = vm.get_3_after_intr_ptr();
+            let (arg1, arg2, dest) = at.get_3_after();
             Ok(Self::Add(
-                (opcode, arg1, arg1_mode, 1).into(),
-                (opcode, arg2, arg2_mode, 2).into(),
-                (opcode, dest, arg3_mode, 3).into(),
+                (opcode, arg1, arg1_mode, 1, at.address).into(),
+                (opcode, arg2, arg2_mode, 2, at.address).into(),
+                (opcode, dest, arg3_mode, 3, at.address).into(),
             ))
         }
 
         #[inline]
-        fn create_mul(
-            vm: &'t IntcodeVM<T>,
+        fn create_mul<M: MemoryRead<T>>(
+            at: InstrAt<'t, M>,
             arg1_mode: ArgMode,
             arg2_mode: ArgMode,
             arg3_mode: ArgMode,
             opcode: u16,
         ) -> error::Result<Self, T> {
-            let (arg1, arg2, dest) = vm.get_3_after_intr_ptr();
+            let (arg1, arg2, dest) = at.get_3_after();
             Ok(Self::Mul(
-                (opcode, arg1, arg1_mode, 1).into(),
-                (opcode, arg2, arg2_mode, 2).into(),
-                (opcode, dest, arg3_mode, 3).into(),
+                (opcode, arg1, arg1_mode, 1, at.address).into(),
+                (opcode, arg2, arg2_mode, 2, at.address).into(),
+                (opcode, dest, arg3_mode, 3, at.address).into(),
             ))
         }
 
         #[inline]
-        fn create_read_input(
-            vm: &'t IntcodeVM<T>,
+        fn create_read_input<M: MemoryRead<T>>(
+            at: InstrAt<'t, M>,
             arg1_mode: ArgMode,
             _arg2_mode: ArgMode,
             _arg3_mode: ArgMode,
             opcode: u16,
         ) -> error::Result<Self, T> {
-            let arg = vm.get_at_instr_ptr(1);
-            Ok(Self::ReadInput((opcode, arg, arg1_mode, 1).into()))
+            if let ArgMode::Immediate = arg1_mode {
+                return Err(VMError::InputTargetImmediate {
+                    opcode,
+                    address: at.address,
+                });
+            }
+
+            let arg = at.get(1);
+            Ok(Self::ReadInput(
+                (opcode, arg, arg1_mode, 1, at.address).into(),
+            ))
         }
 
         #[inline]
-        fn create_write_output(
-            vm: &'t IntcodeVM<T>,
+        fn create_write_output<M: MemoryRead<T>>(
+            at: InstrAt<'t, M>,
             arg1_mode: ArgMode,
             _arg2_mode: ArgMode,
             _arg3_mode: ArgMode,
             opcode: u16,
         ) -> error::Result<Self, T> {
-            let arg = vm.get_at_instr_ptr(1);
-            Ok(Self::WriteOutput((opcode, arg, arg1_mode, 1).into()))
+            let arg = at.get(1);
+            Ok(Self::WriteOutput(
+                (opcode, arg, arg1_mode, 1, at.address).into(),
+            ))
         }
 
         #[inline]
-        fn create_jmp_if_true(
-            vm: &'t IntcodeVM<T>,
+        fn create_jmp_if_true<M: MemoryRead<T>>(
+            at: InstrAt<'t, M>,
             arg1_mode: ArgMode,
             arg2_mode: ArgMode,
             _arg3_mode: ArgMode,
             opcode: u16,
         ) -> error::Result<Self, T> {
-            let (arg1, target) = vm.get_2_after_intr_ptr();
+            let (arg1, target) = at.get_2_after();
             Ok(Self::JmpIfTrue(
-                (opcode, arg1, arg1_mode, 1).into(),
-                (opcode, target, arg2_mode, 2).into(),
+                (opcode, arg1, arg1_mode, 1, at.address).into(),
+                (opcode, target, arg2_mode, 2, at.address).into(),
             ))
         }
 
         #[inline]
-        fn create_jmp_if_false(
-            vm: &'t IntcodeVM<T>,
+        fn create_jmp_if_false<M: MemoryRead<T>>(
+            at: InstrAt<'t, M>,
             arg1_mode: ArgMode,
             arg2_mode: ArgMode,
             _arg3_mode: ArgMode,
             opcode: u16,
         ) -> error::Result<Self, T> {
-            let (arg1, target) = vm.get_2_after_intr_ptr();
+            let (arg1, target) = at.get_2_after();
             Ok(Self::JmpIfFalse(
-                (opcode, arg1, arg1_mode, 1).into(),
-                (opcode, target, arg2_mode, 2).into(),
+                (opcode, arg1, arg1_mode, 1, at.address).into(),
+                (opcode, target, arg2_mode, 2, at.address).into(),
             ))
         }
 
         #[inline]
-        fn create_less_than(
-            vm: &'t IntcodeVM<T>,
+        fn create_less_than<M: MemoryRead<T>>(
+            at: InstrAt<'t, M>,
             arg1_mode: ArgMode,
             arg2_mode: ArgMode,
             arg3_mode: ArgMode,
             opcode: u16,
         ) -> error::Result<Self, T> {
-            let (arg1, arg2, dest) = vm.get_3_after_intr_ptr();
+            let (arg1, arg2, dest) = at.get_3_after();
             Ok(Self::LessThan(
-                (opcode, arg1, arg1_mode, 1).into(),
-                (opcode, arg2, arg2_mode, 2).into(),
-                (opcode, dest, arg3_mode, 3).into(),
+                (opcode, arg1, arg1_mode, 1, at.address).into(),
+                (opcode, arg2, arg2_mode, 2, at.address).into(),
+                (opcode, dest, arg3_mode, 3, at.address).into(),
             ))
         }
 
         #[inline]
-        fn create_equals(
-            vm: &'t IntcodeVM<T>,
+        fn create_equals<M: MemoryRead<T>>(
+            at: InstrAt<'t, M>,
             arg1_mode: ArgMode,
             arg2_mode: ArgMode,
             arg3_mode: ArgMode,
             opcode: u16,
         ) -> error::Result<Self, T> {
-            let (arg1, arg2, dest) = vm.get_3_after_intr_ptr();
+            let (arg1, arg2, dest) = at.get_3_after();
             Ok(Self::Equals(
-                (opcode, arg1, arg1_mode, 1).into(),
-                (opcode, arg2, arg2_mode, 2).into(),
-                (opcode, dest, arg3_mode, 3).into(),
+                (opcode, arg1, arg1_mode, 1, at.address).into(),
+                (opcode, arg2, arg2_mode, 2, at.address).into(),
+                (opcode, dest, arg3_mode, 3, at.address).into(),
             ))
         }
 
         #[inline]
-        fn create_add_relative_base(
-            vm: &'t IntcodeVM<T>,
+        fn create_add_relative_base<M: MemoryRead<T>>(
+            at: InstrAt<'t, M>,
             arg1_mode: ArgMode,
             _arg2_mode: ArgMode,
             _arg3_mode: ArgMode,
             opcode: u16,
         ) -> error::Result<Self, T> {
-            let arg = vm.get_at_instr_ptr(1);
-            Ok(Self::AddRelativeBase((opcode, arg, arg1_mode, 1).into()))
+            let arg = at.get(1);
+            Ok(Self::AddRelativeBase(
+                (opcode, arg, arg1_mode, 1, at.address).into(),
+            ))
         }
 
         #[inline]
-        fn get_3_arg_modes(opcode: u16) -> Result<(ArgMode, ArgMode, ArgMode), VMError<T>> {
+        fn get_3_arg_modes(
+            opcode: u16,
+            address: usize,
+        ) -> Result<(ArgMode, ArgMode, ArgMode), VMError<T>> {
             let mut op = opcode / 100;
             let arg1 = (op % 10) as u8;
             op /= 10;
@@ -544,14 +3456,19 @@ mod instr {
             op /= 10;
             let arg3 = op as u8;
             Ok((
-                Self::parse_arg_mode(opcode, arg1, 1)?,
-                Self::parse_arg_mode(opcode, arg2, 2)?,
-                Self::parse_arg_mode(opcode, arg3, 3)?,
+                Self::parse_arg_mode(opcode, arg1, 1, address)?,
+                Self::parse_arg_mode(opcode, arg2, 2, address)?,
+                Self::parse_arg_mode(opcode, arg3, 3, address)?,
             ))
         }
 
         #[inline]
-        fn parse_arg_mode(opcode: u16, arg_mode: u8, arg_num: u8) -> error::Result<ArgMode, T> {
+        fn parse_arg_mode(
+            opcode: u16,
+            arg_mode: u8,
+            arg_num: u8,
+            address: usize,
+        ) -> error::Result<ArgMode, T> {
             match arg_mode {
                 0 => Ok(ArgMode::Positional),
                 1 => Ok(ArgMode::Immediate),
@@ -560,6 +3477,7 @@ mod instr {
                     opcode,
                     arg_num,
                     arg_mode,
+                    address,
                 }),
             }
         }
@@ -0,0 +1,241 @@
+//! Integers with defined wraparound on overflow (see [WrappingVM])
+
+use core::{
+    fmt,
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+    str::FromStr,
+};
+
+use num::{FromPrimitive, Integer, Num, One, ToPrimitive, Zero};
+
+use crate::IntcodeVM;
+
+/// [VM](IntcodeVM) whose additions and multiplications wrap around on overflow of an [`i64`],
+/// in debug and release builds alike
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::{wrapping::Wrapping, VMResult, WrappingVM};
+/// // outputs i64::MAX + 1
+/// let mut vm = WrappingVM::new([1101, i64::MAX, 1, 0, 4, 0, 99].map(Wrapping));
+/// assert_eq!(vm.run().unwrap(), VMResult::Output(Wrapping(i64::MIN)));
+/// ```
+pub type WrappingVM = IntcodeVM<Wrapping<i64>>;
+
+/// Signed primitive integer whose arithmetic wraps around on overflow
+///
+/// Unlike [`core::num::Wrapping`], it implements [`Integer`] (and the rest of the bounds of a [VM](IntcodeVM)).
+/// Dividing by zero still panics, the division of the minimum value by -1 gives the minimum value back.
+///
+/// # Example
+///
+/// ```
+/// # use intcode_vm::wrapping::Wrapping;
+/// assert_eq!(Wrapping(i32::MAX) + Wrapping(1), Wrapping(i32::MIN));
+/// assert_eq!(Wrapping(i32::MIN) / Wrapping(-1), Wrapping(i32::MIN));
+/// assert_eq!("-5".parse(), Ok(Wrapping(-5i32)));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(transparent)]
+pub struct Wrapping<T>(pub T);
+
+impl<T: fmt::Debug> fmt::Debug for Wrapping<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Wrapping<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+macro_rules! impl_wrapping {
+    ($($t:ty)*) => {$(
+        impl Add for Wrapping<$t> {
+            type Output = Self;
+
+            #[inline]
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0.wrapping_add(rhs.0))
+            }
+        }
+
+        impl Sub for Wrapping<$t> {
+            type Output = Self;
+
+            #[inline]
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0.wrapping_sub(rhs.0))
+            }
+        }
+
+        impl Mul for Wrapping<$t> {
+            type Output = Self;
+
+            #[inline]
+            fn mul(self, rhs: Self) -> Self {
+                Self(self.0.wrapping_mul(rhs.0))
+            }
+        }
+
+        impl Div for Wrapping<$t> {
+            type Output = Self;
+
+            #[inline]
+            fn div(self, rhs: Self) -> Self {
+                Self(self.0.wrapping_div(rhs.0))
+            }
+        }
+
+        impl Rem for Wrapping<$t> {
+            type Output = Self;
+
+            #[inline]
+            fn rem(self, rhs: Self) -> Self {
+                Self(self.0.wrapping_rem(rhs.0))
+            }
+        }
+
+        impl Neg for Wrapping<$t> {
+            type Output = Self;
+
+            #[inline]
+            fn neg(self) -> Self {
+                Self(self.0.wrapping_neg())
+            }
+        }
+
+        impl Zero for Wrapping<$t> {
+            #[inline]
+            fn zero() -> Self {
+                Self(0)
+            }
+
+            #[inline]
+            fn is_zero(&self) -> bool {
+                self.0 == 0
+            }
+        }
+
+        impl One for Wrapping<$t> {
+            #[inline]
+            fn one() -> Self {
+                Self(1)
+            }
+        }
+
+        impl Num for Wrapping<$t> {
+            type FromStrRadixErr = <$t as Num>::FromStrRadixErr;
+
+            #[inline]
+            fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+                <$t>::from_str_radix(s, radix).map(Self)
+            }
+        }
+
+        impl Integer for Wrapping<$t> {
+            #[inline]
+            fn div_floor(&self, other: &Self) -> Self {
+                match other.0 {
+                    -1 => -*self,
+                    other => Self(Integer::div_floor(&self.0, &other)),
+                }
+            }
+
+            #[inline]
+            fn mod_floor(&self, other: &Self) -> Self {
+                match other.0 {
+                    -1 => Self(0),
+                    other => Self(Integer::mod_floor(&self.0, &other)),
+                }
+            }
+
+            #[inline]
+            fn gcd(&self, other: &Self) -> Self {
+                Self(Integer::gcd(&self.0, &other.0))
+            }
+
+            #[inline]
+            fn lcm(&self, other: &Self) -> Self {
+                Self(Integer::lcm(&self.0, &other.0))
+            }
+
+            #[inline]
+            fn is_multiple_of(&self, other: &Self) -> bool {
+                Integer::is_multiple_of(&self.0, &other.0)
+            }
+
+            #[inline]
+            fn is_even(&self) -> bool {
+                Integer::is_even(&self.0)
+            }
+
+            #[inline]
+            fn is_odd(&self) -> bool {
+                Integer::is_odd(&self.0)
+            }
+
+            #[inline]
+            fn div_rem(&self, other: &Self) -> (Self, Self) {
+                (*self / *other, *self % *other)
+            }
+        }
+
+        impl ToPrimitive for Wrapping<$t> {
+            #[inline]
+            fn to_i64(&self) -> Option<i64> {
+                self.0.to_i64()
+            }
+
+            #[inline]
+            fn to_u64(&self) -> Option<u64> {
+                self.0.to_u64()
+            }
+
+            #[inline]
+            fn to_i128(&self) -> Option<i128> {
+                self.0.to_i128()
+            }
+
+            #[inline]
+            fn to_u128(&self) -> Option<u128> {
+                self.0.to_u128()
+            }
+        }
+
+        impl FromPrimitive for Wrapping<$t> {
+            #[inline]
+            fn from_i64(n: i64) -> Option<Self> {
+                <$t>::from_i64(n).map(Self)
+            }
+
+            #[inline]
+            fn from_u64(n: u64) -> Option<Self> {
+                <$t>::from_u64(n).map(Self)
+            }
+        }
+
+        impl From<u8> for Wrapping<$t> {
+            #[inline]
+            fn from(value: u8) -> Self {
+                Self(value.into())
+            }
+        }
+
+        impl FromStr for Wrapping<$t> {
+            type Err = <$t as FromStr>::Err;
+
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.parse().map(Self)
+            }
+        }
+    )*};
+}
+
+impl_wrapping!(i16 i32 i64 i128 isize);
@@ -24,6 +24,7 @@ fn part1(input: &str) -> Result<i64, Box<dyn Error>> {
             VMResult::Halted => {
                 return Ok(last_diagnostic);
             }
+            other => return Err(format!("Unexpected VM result: {:?}", other).into()),
         }
     }
 }
@@ -37,6 +38,7 @@ fn part2(input: &str) -> Result<i64, Box<dyn Error>> {
         VMResult::Output(out) => out,
         VMResult::WaitingForInput => Err("VM asked for input beyond the `5` already provided")?,
         VMResult::Halted => Err("VM halted without producing any values")?,
+        other => Err(format!("Expected an output, got {:?}", other))?,
     };
 
     assert_eq!(vm.run()?, VMResult::Halted);
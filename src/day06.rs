@@ -34,7 +34,7 @@ impl Graph {
 
             for key in &self.adj_list[key] {
                 if visited.insert(key) {
-                    queue.push_back((&key, depth + 1));
+                    queue.push_back((key, depth + 1));
                 }
             }
         }
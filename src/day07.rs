@@ -61,6 +61,7 @@ fn part2(program: &[i64]) -> Result<i64, Box<dyn Error>> {
                         VMResult::WaitingForInput => (),
                         VMResult::Halted => break 'outer current_thrust,
                         VMResult::Output(v) => Err(format!("VM outputed {v} unexpectedly"))?,
+                        other => Err(format!("Unexpected VM result: {:?}", other))?,
                     }
 
                     vm.set_next_input(current_thrust);
@@ -88,6 +88,7 @@ fn part1(input: &str) -> Result<usize, Box<dyn Error>> {
                 vm.set_next_input(painted_map.get(&current_pos).map_or(0, PanelColor::as_i64));
             }
             VMResult::Output(out) => panic!("Expected program to ask for input, outputed {}", out),
+            other => panic!("Unexpected VM result: {:?}", other),
         }
 
         match vm.run()? {
@@ -103,6 +104,7 @@ fn part1(input: &str) -> Result<usize, Box<dyn Error>> {
                     },
                 );
             }
+            other => panic!("Unexpected VM result: {:?}", other),
         }
 
         match vm.run()? {
@@ -119,6 +121,7 @@ fn part1(input: &str) -> Result<usize, Box<dyn Error>> {
                 }
                 other => panic!("Direction to turn to was neither 0 nor 1 ({})", other),
             },
+            other => panic!("Unexpected VM result: {:?}", other),
         }
 
         current_pos = current_direction.translate_pos(current_pos);
@@ -142,6 +145,7 @@ fn part2(input: &str) -> Result<String, Box<dyn Error>> {
                 vm.set_next_input(painted_map.get(&current_pos).map_or(0, PanelColor::as_i64));
             }
             VMResult::Output(out) => panic!("Expected program to ask for input, outputed {}", out),
+            other => panic!("Unexpected VM result: {:?}", other),
         }
 
         match vm.run()? {
@@ -157,6 +161,7 @@ fn part2(input: &str) -> Result<String, Box<dyn Error>> {
                     },
                 );
             }
+            other => panic!("Unexpected VM result: {:?}", other),
         }
 
         match vm.run()? {
@@ -173,6 +178,7 @@ fn part2(input: &str) -> Result<String, Box<dyn Error>> {
                 }
                 other => panic!("Direction to turn to was neither 0 nor 1 ({})", other),
             },
+            other => panic!("Unexpected VM result: {:?}", other),
         }
 
         current_pos = current_direction.translate_pos(current_pos);
@@ -119,18 +119,21 @@ fn decode_draw(vm: &mut IntcodeVM<i64>) -> Result<DecodeDraw, Box<dyn Error>> {
         VMResult::Output(v) => v,
         VMResult::WaitingForInput => return Ok(DecodeDraw::WaitingForInput),
         VMResult::Halted => return Ok(DecodeDraw::Halt),
+        other => Err(format!("Unexpected VM result: {:?}", other))?,
     };
 
     let y = match vm.run()? {
         VMResult::Output(v) => v,
         VMResult::WaitingForInput => return Ok(DecodeDraw::WaitingForInput),
         VMResult::Halted => return Ok(DecodeDraw::Halt),
+        other => Err(format!("Unexpected VM result: {:?}", other))?,
     };
 
     let id = match vm.run()? {
         VMResult::Output(v) => v,
         VMResult::WaitingForInput => return Ok(DecodeDraw::WaitingForInput),
         VMResult::Halted => return Ok(DecodeDraw::Halt),
+        other => Err(format!("Unexpected VM result: {:?}", other))?,
     };
 
     Ok(if x == -1 && y == 0 {